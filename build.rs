@@ -1,3 +1,6 @@
 fn main() {
-    slint_build::compile("ui/app-window.slint").expect("Slint build failed");
+    // Translations for `@tr` strings are compiled in from `lang/<lang>/LC_MESSAGES`.
+    println!("cargo:rerun-if-changed=lang");
+    let config = slint_build::CompilerConfiguration::new().with_bundled_translations("lang");
+    slint_build::compile_with_config("ui/app-window.slint", config).expect("Slint build failed");
 }
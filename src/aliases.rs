@@ -0,0 +1,65 @@
+//! Display names for portraits whose file name can't spell them, e.g.
+//! `the_legion=The Legion (Frank)`. Lives in `aliases.txt` next to `media/`.
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs,
+    io::{ErrorKind, Write},
+    path::Path,
+};
+
+pub const ALIASES_FILE: &str = "aliases.txt";
+
+/// Parse `stem=Display Name` lines, skipping blanks, `#` comments and malformed lines.
+fn parse(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .filter_map(|line| {
+            let alias = line.split_once('=').and_then(|(stem, name)| {
+                let (stem, name) = (stem.trim(), name.trim());
+                (!stem.is_empty() && !name.is_empty()).then(|| (stem.to_string(), name.to_string()))
+            });
+            if alias.is_none() {
+                log::warn!("Ignoring alias {:?} in {}", line, ALIASES_FILE);
+            }
+            alias
+        })
+        .collect()
+}
+
+/// Load the aliases in `base`, creating an empty file with instructions if it's missing.
+pub fn load(base: &Path) -> HashMap<String, String> {
+    let path = base.join(ALIASES_FILE);
+    match fs::read_to_string(&path) {
+        Ok(text) => parse(&text),
+        Err(e) => {
+            if e.kind() != ErrorKind::NotFound {
+                log::warn!("Could not read {}: {}", path.display(), e);
+            } else if let Err(e) = create_default_file(&path) {
+                log::warn!("Could not create {}: {}", path.display(), e);
+            }
+            HashMap::new()
+        }
+    }
+}
+
+fn create_default_file(path: &Path) -> Result<(), Box<dyn Error>> {
+    let mut file = fs::File::create(path)?;
+    writeln!(file, "# Display Names")?;
+    writeln!(
+        file,
+        "# Each line maps a portrait's file name (without .png) to the name shown:"
+    )?;
+    writeln!(file, "# the_legion=The Legion (Frank)")?;
+    writeln!(
+        file,
+        "# Portraits not listed here are named after their file, so TheTrapper.png"
+    )?;
+    writeln!(file, "# shows as \"The Trapper\".")?;
+    writeln!(
+        file,
+        "# Lines starting with # are comments and will be ignored."
+    )?;
+    Ok(())
+}
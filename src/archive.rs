@@ -0,0 +1,142 @@
+//! Backups of everything in one zip: the save file, category and promotion files, and
+//! optionally the `media/` portraits, for restoring or moving to another PC.
+use crate::{
+    backup_save_file, promotions::PROMOTIONS_FILE, schema, Character, BACKUP_DIR,
+    KILLER_STREAKS_FILE, MEDIA_DIR, STREAKS_FILE, SURVIVOR_STREAKS_FILE,
+};
+use std::{
+    error::Error,
+    fs::{self, File},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+use zip::{write::SimpleFileOptions, ZipArchive, ZipWriter};
+
+/// Archive used when no path is given, relative to the base directory.
+pub const DEFAULT_ARCHIVE: &str = "dbd-winstreak-backup.zip";
+/// Files taken from the profile's directory, stored at the top of the archive.
+const DATA_FILES: &[&str] = &[
+    STREAKS_FILE,
+    KILLER_STREAKS_FILE,
+    SURVIVOR_STREAKS_FILE,
+    PROMOTIONS_FILE,
+];
+
+/// Write `dir`'s data files, plus `base/media` if `include_media`, to `path`.
+pub fn export(
+    path: &Path,
+    dir: &Path,
+    base: &Path,
+    include_media: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut zip = ZipWriter::new(File::create(path)?);
+    let options = SimpleFileOptions::default();
+    for name in DATA_FILES {
+        let file = dir.join(name);
+        if file.exists() {
+            zip.start_file(*name, options)?;
+            zip.write_all(&fs::read(file)?)?;
+        }
+    }
+    if include_media {
+        for file in media_files(&base.join(MEDIA_DIR))? {
+            let relative = file.strip_prefix(base)?;
+            // Zip entries always use forward slashes.
+            let name = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            zip.start_file(name, options)?;
+            zip.write_all(&fs::read(&file)?)?;
+        }
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Every file under `dir`, including subfolders such as `media/survivors`.
+fn media_files(dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut files = Vec::new();
+    if !dir.is_dir() {
+        return Ok(files);
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(media_files(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// Restore an archive made by [`export`]: data files into `dir`, portraits into
+/// `base/media`. Everything is read and checked before anything is written, so an
+/// archive with a broken save file changes nothing.
+pub fn import(path: &Path, dir: &Path, base: &Path) -> Result<(), Box<dyn Error>> {
+    let mut zip = ZipArchive::new(File::open(path)?)?;
+    let mut data = Vec::new();
+    let mut media = Vec::new();
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(name) = entry.enclosed_name() else {
+            log::warn!(
+                "Skipping unsafe path {:?} in {}",
+                entry.name(),
+                path.display()
+            );
+            continue;
+        };
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes)?;
+        if name.starts_with(MEDIA_DIR) {
+            media.push((base.join(name), bytes));
+        } else if let Some(file) = DATA_FILES.iter().find(|f| name == Path::new(f)) {
+            data.push((*file, bytes));
+        }
+    }
+
+    let Some((_, streaks)) = data.iter().find(|(name, _)| *name == STREAKS_FILE) else {
+        return Err(format!("{} has no {}", path.display(), STREAKS_FILE).into());
+    };
+    schema::parse(std::str::from_utf8(streaks)?)
+        .map_err(|e| format!("{} in {} is invalid: {}", STREAKS_FILE, path.display(), e))?;
+    for (name, bytes) in &data {
+        std::str::from_utf8(bytes).map_err(|e| format!("{} is not text: {}", name, e))?;
+    }
+
+    fs::create_dir_all(dir)?;
+    let json = dir.join(STREAKS_FILE);
+    if let Err(e) = backup_save_file(&json, &dir.join(BACKUP_DIR)) {
+        log::warn!("Could not back up {}: {}", json.display(), e);
+    }
+    for (name, bytes) in data {
+        fs::write(dir.join(name), bytes)?;
+    }
+    for (file, bytes) in media {
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(file, bytes)?;
+    }
+    Ok(())
+}
+
+/// Only the characters saved in an archive made by [`export`], for merging them into
+/// the current saves instead of replacing them.
+pub fn read_saves(path: &Path) -> Result<Vec<Character>, Box<dyn Error>> {
+    let mut zip = ZipArchive::new(File::open(path)?)?;
+    let mut entry = zip
+        .by_name(STREAKS_FILE)
+        .map_err(|_| format!("{} has no {}", path.display(), STREAKS_FILE))?;
+    let mut text = String::new();
+    entry.read_to_string(&mut text)?;
+    let (characters, _) = schema::parse(&text)
+        .map_err(|e| format!("{} in {} is invalid: {}", STREAKS_FILE, path.display(), e))?;
+    Ok(characters)
+}
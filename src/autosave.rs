@@ -0,0 +1,199 @@
+//! Debounced saving. Changes only mark the data dirty and wake a background thread
+//! over a channel; it waits out the interval, so a burst of changes is written once,
+//! and then serializes and writes `streaks.json` off the UI thread.
+//!
+//! Saving also guards against clobbering edits made outside the app: the hash of the
+//! file as last loaded or written is kept, and if the file on disk no longer matches
+//! it, saving stops until the user picks which version to keep.
+use crate::{lock, save_or_log, Character, STREAKS_FILE};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{self, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AutosaveConfig {
+    /// Longest a change waits before it's written, in seconds.
+    pub interval_secs: u64,
+}
+
+impl Default for AutosaveConfig {
+    fn default() -> Self {
+        Self { interval_secs: 1 }
+    }
+}
+
+struct Inner {
+    characters: Arc<Mutex<Vec<Character>>>,
+    dir: Mutex<PathBuf>,
+    dirty: AtomicBool,
+    interval_secs: AtomicU64,
+    /// Hash of `streaks.json` as we last loaded or wrote it.
+    known_hash: Mutex<Option<u64>>,
+    /// Set when the file changed behind our back; saving waits until it's cleared.
+    conflict: AtomicBool,
+    /// Set in practice mode, when changes are never written.
+    paused: AtomicBool,
+    // Held while saving so an older snapshot never lands after a newer one.
+    writing: Mutex<()>,
+}
+
+impl Inner {
+    fn path(&self) -> PathBuf {
+        lock(&self.dir).join(STREAKS_FILE)
+    }
+
+    /// Whether the file on disk differs from the one we know about. A missing file
+    /// isn't a conflict; it's simply written again.
+    fn changed_on_disk(&self, path: &Path) -> bool {
+        let on_disk = file_hash(path);
+        on_disk.is_some() && on_disk != *lock(&self.known_hash)
+    }
+
+    fn flush(&self) {
+        let _writing = lock(&self.writing);
+        if self.paused.load(Ordering::SeqCst)
+            || self.conflict.load(Ordering::SeqCst)
+            || !self.dirty.swap(false, Ordering::SeqCst)
+        {
+            return;
+        }
+        let path = self.path();
+        if self.changed_on_disk(&path) {
+            log::warn!("{} was changed outside the app; not saving", path.display());
+            self.dirty.store(true, Ordering::SeqCst);
+            self.conflict.store(true, Ordering::SeqCst);
+            return;
+        }
+        let snapshot = lock(&self.characters).clone();
+        let dir = lock(&self.dir).clone();
+        save_or_log(&dir, &snapshot);
+        *lock(&self.known_hash) = file_hash(&path);
+    }
+}
+
+/// Hash of a file's contents, or `None` if it can't be read.
+fn file_hash(path: &Path) -> Option<u64> {
+    let bytes = fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+pub struct Autosave {
+    inner: Arc<Inner>,
+    /// Wakes the saver; each message asks for one save of whatever is current then.
+    wake: Sender<()>,
+}
+
+impl Autosave {
+    /// Start the background saver for `characters`, saving into `dir`.
+    pub fn start(
+        characters: Arc<Mutex<Vec<Character>>>,
+        dir: PathBuf,
+        config: &AutosaveConfig,
+    ) -> Self {
+        let known_hash = file_hash(&dir.join(STREAKS_FILE));
+        let inner = Arc::new(Inner {
+            characters,
+            dir: Mutex::new(dir),
+            dirty: AtomicBool::new(false),
+            interval_secs: AtomicU64::new(config.interval_secs.max(1)),
+            known_hash: Mutex::new(known_hash),
+            conflict: AtomicBool::new(false),
+            paused: AtomicBool::new(false),
+            writing: Mutex::new(()),
+        });
+        let (wake, requests) = mpsc::channel::<()>();
+        thread::spawn({
+            let inner = inner.clone();
+            move || {
+                while requests.recv().is_ok() {
+                    let secs = inner.interval_secs.load(Ordering::Relaxed);
+                    thread::sleep(Duration::from_secs(secs));
+                    // Whatever arrived meanwhile is covered by this save.
+                    requests.try_iter().for_each(drop);
+                    inner.flush();
+                }
+            }
+        });
+        Self { inner, wake }
+    }
+
+    /// Schedule a save of the current data.
+    pub fn mark_dirty(&self) {
+        self.inner.dirty.store(true, Ordering::SeqCst);
+        // Only fails once the saver is gone, and then nothing is left to wake.
+        let _ = self.wake.send(());
+    }
+
+    /// Change how long changes may wait, starting after the current wait.
+    pub fn set_interval(&self, secs: u64) {
+        self.inner
+            .interval_secs
+            .store(secs.max(1), Ordering::Relaxed);
+    }
+
+    /// Write any pending changes now, e.g. before exiting. Must not be called while
+    /// holding the characters lock.
+    pub fn flush(&self) {
+        self.inner.flush();
+    }
+
+    /// Save into `dir` from now on, once its data has just been loaded. Anything
+    /// pending for the old directory should be flushed first.
+    pub fn set_dir(&self, dir: PathBuf) {
+        let _writing = lock(&self.inner.writing);
+        *lock(&self.inner.known_hash) = file_hash(&dir.join(STREAKS_FILE));
+        *lock(&self.inner.dir) = dir;
+        self.inner.dirty.store(false, Ordering::SeqCst);
+        self.inner.conflict.store(false, Ordering::SeqCst);
+    }
+
+    /// Write what's pending, then stop saving until [`Autosave::resume`].
+    pub fn pause(&self) {
+        self.inner.flush();
+        self.inner.paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Start saving again, dropping whatever changed while paused. The data in memory
+    /// should be back to what was saved before the pause.
+    pub fn resume(&self) {
+        let _writing = lock(&self.inner.writing);
+        self.inner.dirty.store(false, Ordering::SeqCst);
+        self.inner.paused.store(false, Ordering::SeqCst);
+    }
+
+    /// Check the file right away, e.g. when a watcher reports it changed.
+    pub fn check_for_external_edit(&self) {
+        let _writing = lock(&self.inner.writing);
+        if self.inner.changed_on_disk(&self.inner.path()) {
+            self.inner.conflict.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Whether the file changed outside the app and the user hasn't decided yet.
+    pub fn has_conflict(&self) -> bool {
+        self.inner.conflict.load(Ordering::SeqCst)
+    }
+
+    /// Resolve a conflict by overwriting the file with the data in memory.
+    pub fn keep_ours(&self) {
+        let _writing = lock(&self.inner.writing);
+        *lock(&self.inner.known_hash) = file_hash(&self.inner.path());
+        self.inner.conflict.store(false, Ordering::SeqCst);
+        self.inner.dirty.store(true, Ordering::SeqCst);
+        let _ = self.wake.send(());
+    }
+}
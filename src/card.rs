@@ -0,0 +1,316 @@
+//! A shareable PNG card of chosen characters and their best streaks, sized for social
+//! media posts. Drawn with `tiny-skia`, with text from the bundled Open Sans.
+use crate::{numbers, parse_hex_color, Character};
+use ab_glyph::{point, Font, FontRef, PxScale, ScaleFont};
+use std::{
+    cmp::Reverse,
+    error::Error,
+    path::{Path, PathBuf},
+};
+use tiny_skia::{
+    Color, GradientStop, IntSize, LinearGradient, Paint, PathBuilder, Pixmap, PixmapPaint, Point,
+    Rect, SpreadMode, Transform,
+};
+
+/// Card written by [`write`], into the data directory.
+pub const CARD_FILE: &str = "stats-card.png";
+/// Characters that fit on one card, in two columns of three.
+pub const MAX_CHARACTERS: usize = 6;
+/// Categories listed under each character; the best ones are kept.
+const MAX_CATEGORIES: usize = 4;
+/// 16:9, which the usual sites show uncropped.
+const WIDTH: u32 = 1200;
+const HEIGHT: u32 = 675;
+const MARGIN: f32 = 40.0;
+const TOP: f32 = 110.0;
+const CELL_WIDTH: f32 = 550.0;
+const CELL_HEIGHT: f32 = 165.0;
+const ROW_GAP: f32 = 15.0;
+const COLUMN_GAP: f32 = 20.0;
+const PORTRAIT: u32 = 135;
+const FONT: &[u8] = include_bytes!("../media/OpenSans.ttf");
+
+const TEXT: [u8; 3] = [0xFF, 0xFF, 0xFF];
+const MUTED_TEXT: [u8; 3] = [0xBB, 0xBB, 0xBB];
+/// Strip beside characters without a color of their own.
+const DEFAULT_ACCENT: [u8; 3] = [0xB0, 0x20, 0x20];
+
+/// Draw `characters`, each with those of its categories named in `categories`, and
+/// write the card into `dir`. Portrait paths are relative to `media_root`. Only the
+/// first [`MAX_CHARACTERS`] are drawn.
+pub fn write(
+    dir: &Path,
+    media_root: &Path,
+    characters: &[Character],
+    categories: &[String],
+) -> Result<PathBuf, Box<dyn Error>> {
+    let font = FontRef::try_from_slice(FONT)?;
+    let mut pixmap = Pixmap::new(WIDTH, HEIGHT).ok_or("Could not allocate the card")?;
+    draw_background(&mut pixmap);
+    draw_text(
+        &mut pixmap,
+        &font,
+        "DBD Winstreak",
+        MARGIN,
+        75.0,
+        44.0,
+        TEXT,
+    );
+    let date = chrono::Local::now().date_naive().to_string();
+    let date_x = WIDTH as f32 - MARGIN - text_width(&font, &date, 22.0);
+    draw_text(&mut pixmap, &font, &date, date_x, 72.0, 22.0, MUTED_TEXT);
+
+    for (i, character) in characters.iter().take(MAX_CHARACTERS).enumerate() {
+        let x = MARGIN + (i % 2) as f32 * (CELL_WIDTH + COLUMN_GAP);
+        let y = TOP + (i / 2) as f32 * (CELL_HEIGHT + ROW_GAP);
+        draw_character(&mut pixmap, &font, media_root, character, categories, x, y);
+    }
+
+    let path = dir.join(CARD_FILE);
+    pixmap.save_png(&path)?;
+    Ok(path)
+}
+
+fn draw_background(pixmap: &mut Pixmap) {
+    let shader = LinearGradient::new(
+        Point::from_xy(0.0, 0.0),
+        Point::from_xy(WIDTH as f32, HEIGHT as f32),
+        vec![
+            GradientStop::new(0.0, Color::from_rgba8(0x1B, 0x1B, 0x22, 0xFF)),
+            GradientStop::new(1.0, Color::from_rgba8(0x2E, 0x14, 0x14, 0xFF)),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    )
+    .expect("two stops over a non-empty line");
+    let paint = Paint {
+        shader,
+        ..Paint::default()
+    };
+    let rect = Rect::from_xywh(0.0, 0.0, WIDTH as f32, HEIGHT as f32).expect("card size");
+    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+}
+
+fn draw_character(
+    pixmap: &mut Pixmap,
+    font: &FontRef,
+    media_root: &Path,
+    character: &Character,
+    categories: &[String],
+    x: f32,
+    y: f32,
+) {
+    fill_rounded(
+        pixmap,
+        x,
+        y,
+        CELL_WIDTH,
+        CELL_HEIGHT,
+        16.0,
+        [0x2B, 0x2B, 0x30],
+        0xE0,
+    );
+    let accent = character
+        .color
+        .as_deref()
+        .and_then(parse_hex_color)
+        .map_or(DEFAULT_ACCENT, |c| [c.red(), c.green(), c.blue()]);
+    fill_rounded(
+        pixmap,
+        x,
+        y + 15.0,
+        6.0,
+        CELL_HEIGHT - 30.0,
+        3.0,
+        accent,
+        0xFF,
+    );
+
+    let (px, py) = (x + 15.0, y + 15.0);
+    match portrait(media_root, character) {
+        Some(image) => pixmap.draw_pixmap(
+            px as i32,
+            py as i32,
+            image.as_ref(),
+            &PixmapPaint::default(),
+            Transform::identity(),
+            None,
+        ),
+        None => fill_rounded(
+            pixmap,
+            px,
+            py,
+            PORTRAIT as f32,
+            PORTRAIT as f32,
+            8.0,
+            [0x44, 0x44, 0x4A],
+            0xFF,
+        ),
+    }
+
+    let text_x = px + PORTRAIT as f32 + 15.0;
+    let right = x + CELL_WIDTH - 20.0;
+    let name = elide(font, &character.name, 28.0, right - text_x);
+    draw_text(pixmap, font, &name, text_x, y + 48.0, 28.0, TEXT);
+
+    let mut listed: Vec<_> = character
+        .streaks
+        .iter()
+        .filter(|cat| categories.contains(&cat.name))
+        .collect();
+    listed.sort_by_key(|cat| Reverse(cat.best));
+    for (row, cat) in listed.iter().take(MAX_CATEGORIES).enumerate() {
+        let baseline = y + 80.0 + row as f32 * 25.0;
+        let best = format!("PB {}", numbers::format(cat.best));
+        let best_x = right - text_width(font, &best, 20.0);
+        draw_text(pixmap, font, &best, best_x, baseline, 20.0, TEXT);
+        let label = elide(font, &cat.name, 20.0, best_x - text_x - 12.0);
+        draw_text(pixmap, font, &label, text_x, baseline, 20.0, MUTED_TEXT);
+    }
+}
+
+/// The character's portrait framed as in the app and scaled to the card, or `None`
+/// if it has none or it can't be read.
+fn portrait(media_root: &Path, character: &Character) -> Option<Pixmap> {
+    if character.image_path.is_empty() {
+        return None;
+    }
+    let image = image::open(media_root.join(&character.image_path))
+        .map_err(|e| log::warn!("Could not load portrait {}: {}", character.image_path, e))
+        .ok()?;
+    let crop = character.image_crop.unwrap_or_default();
+    let zoom = crop.zoom.max(1.0);
+    let (width, height) = (
+        (image.width() as f32 / zoom) as u32,
+        (image.height() as f32 / zoom) as u32,
+    );
+    let left = ((image.width() - width) as f32 * crop.x) as u32;
+    let top = ((image.height() - height) as f32 * crop.y) as u32;
+    let framed = image
+        .crop_imm(left, top, width.max(1), height.max(1))
+        .resize_exact(PORTRAIT, PORTRAIT, image::imageops::FilterType::Triangle);
+    let mut data = framed.to_rgba8().into_raw();
+    // tiny-skia wants premultiplied alpha.
+    for pixel in data.chunks_exact_mut(4) {
+        let alpha = pixel[3] as u16;
+        for channel in &mut pixel[..3] {
+            *channel = (*channel as u16 * alpha / 255) as u8;
+        }
+    }
+    Pixmap::from_vec(data, IntSize::from_wh(PORTRAIT, PORTRAIT)?)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn fill_rounded(
+    pixmap: &mut Pixmap,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    radius: f32,
+    rgb: [u8; 3],
+    alpha: u8,
+) {
+    let (right, bottom) = (x + width, y + height);
+    let mut path = PathBuilder::new();
+    path.move_to(x + radius, y);
+    path.line_to(right - radius, y);
+    path.quad_to(right, y, right, y + radius);
+    path.line_to(right, bottom - radius);
+    path.quad_to(right, bottom, right - radius, bottom);
+    path.line_to(x + radius, bottom);
+    path.quad_to(x, bottom, x, bottom - radius);
+    path.line_to(x, y + radius);
+    path.quad_to(x, y, x + radius, y);
+    path.close();
+    let Some(path) = path.finish() else {
+        return;
+    };
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(rgb[0], rgb[1], rgb[2], alpha);
+    paint.anti_alias = true;
+    pixmap.fill_path(
+        &path,
+        &paint,
+        tiny_skia::FillRule::Winding,
+        Transform::identity(),
+        None,
+    );
+}
+
+fn text_width(font: &FontRef, text: &str, size: f32) -> f32 {
+    let font = font.as_scaled(PxScale::from(size));
+    let mut width = 0.0;
+    let mut previous = None;
+    for c in text.chars() {
+        let id = font.glyph_id(c);
+        if let Some(previous) = previous {
+            width += font.kern(previous, id);
+        }
+        width += font.h_advance(id);
+        previous = Some(id);
+    }
+    width
+}
+
+/// `text`, cut short with an ellipsis if it's wider than `max_width`.
+fn elide(font: &FontRef, text: &str, size: f32, max_width: f32) -> String {
+    if text_width(font, text, size) <= max_width {
+        return text.to_string();
+    }
+    let mut chars: Vec<char> = text.chars().collect();
+    while !chars.is_empty() {
+        chars.pop();
+        let candidate = format!("{}…", chars.iter().collect::<String>().trim_end());
+        if text_width(font, &candidate, size) <= max_width {
+            return candidate;
+        }
+    }
+    String::new()
+}
+
+/// Draw one line of text with its baseline at `baseline`.
+fn draw_text(
+    pixmap: &mut Pixmap,
+    font: &FontRef,
+    text: &str,
+    x: f32,
+    baseline: f32,
+    size: f32,
+    rgb: [u8; 3],
+) {
+    let scaled = font.as_scaled(PxScale::from(size));
+    let (width, height) = (pixmap.width() as i32, pixmap.height() as i32);
+    let data = pixmap.data_mut();
+    let mut caret = x;
+    let mut previous = None;
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(previous) = previous {
+            caret += scaled.kern(previous, id);
+        }
+        let glyph = id.with_scale_and_position(size, point(caret, baseline));
+        caret += scaled.h_advance(id);
+        previous = Some(id);
+        let Some(outline) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outline.px_bounds();
+        outline.draw(|gx, gy, coverage| {
+            let px = bounds.min.x as i32 + gx as i32;
+            let py = bounds.min.y as i32 + gy as i32;
+            if px < 0 || py < 0 || px >= width || py >= height {
+                return;
+            }
+            // Source-over onto premultiplied pixels.
+            let i = (py * width + px) as usize * 4;
+            let coverage = coverage.clamp(0.0, 1.0);
+            for (channel, value) in rgb.iter().enumerate() {
+                let dst = data[i + channel] as f32;
+                data[i + channel] = (*value as f32 * coverage + dst * (1.0 - coverage)) as u8;
+            }
+            let dst = data[i + 3] as f32;
+            data[i + 3] = (255.0 * coverage + dst * (1.0 - coverage)) as u8;
+        });
+    }
+}
@@ -0,0 +1,238 @@
+//! Command-line interface. With no subcommand the GUI starts as usual; subcommands
+//! work on the save data headlessly and exit without creating a window.
+use crate::{config::CounterConfig, merge, Character};
+use clap::{ArgGroup, Parser, Subcommand};
+use std::{
+    error::Error,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+#[derive(Parser, Debug)]
+#[command(version, about = "Track Dead by Daylight win streaks")]
+pub struct Cli {
+    /// Folder holding the config, saves and media [default: the OS config and data
+    /// folders, or the app's folder if portable.txt is next to it]
+    #[arg(long, global = true, env = crate::paths::DATA_DIR_ENV)]
+    pub data_dir: Option<PathBuf>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Print every character's streaks and exit
+    Stats {
+        /// Dump the raw character data as JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+    /// Record a win or loss for one character's category and exit
+    #[command(group(ArgGroup::new("result").required(true).args(["win", "loss"])))]
+    Record {
+        /// Character name, e.g. "The Nurse"
+        #[arg(long)]
+        killer: String,
+        /// Streak category, e.g. "4k"
+        #[arg(long)]
+        category: String,
+        /// Record a win
+        #[arg(long)]
+        win: bool,
+        /// Record a loss, resetting the current streak
+        #[arg(long)]
+        loss: bool,
+    },
+    /// List the categories whose streaks differ between two save files and exit
+    Compare {
+        /// A streaks.json, e.g. from this PC
+        first: PathBuf,
+        /// Another streaks.json, e.g. from a second PC
+        second: PathBuf,
+        /// Also write the first file with the higher best of each category from
+        /// either, plus what only the second has, to this path
+        #[arg(long, value_name = "FILE")]
+        merged: Option<PathBuf>,
+    },
+}
+
+/// Run a headless subcommand against the characters loaded from `dir`.
+pub fn run(
+    command: Command,
+    dir: &Path,
+    limits: &CounterConfig,
+    characters: &mut [Character],
+) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::Stats { json } => print_stats(characters, json),
+        Command::Record {
+            killer,
+            category,
+            win,
+            ..
+        } => record(dir, limits, characters, &killer, &category, win),
+        Command::Compare {
+            first,
+            second,
+            merged,
+        } => compare(&first, &second, merged.as_deref()),
+    }
+}
+
+/// Apply one result the same way the window's win/loss buttons do, then save.
+fn record(
+    dir: &Path,
+    limits: &CounterConfig,
+    characters: &mut [Character],
+    killer: &str,
+    category: &str,
+    is_win: bool,
+) -> Result<(), Box<dyn Error>> {
+    let char_idx = characters
+        .iter()
+        .position(|c| c.name.eq_ignore_ascii_case(killer))
+        .ok_or_else(|| format!("unknown killer \"{}\"", killer))?;
+    let character = &characters[char_idx];
+    let s_idx = character
+        .streaks
+        .iter()
+        .position(|s| s.name.eq_ignore_ascii_case(category))
+        .ok_or_else(|| {
+            format!(
+                "\"{}\" has no category \"{}\" (available: {})",
+                character.name,
+                category,
+                character
+                    .streaks
+                    .iter()
+                    .map(|s| s.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })?;
+
+    let (action, _) = crate::record_result(
+        characters,
+        &crate::promotions::load(dir),
+        limits,
+        char_idx,
+        s_idx,
+        is_win,
+    );
+    let character = &characters[char_idx];
+    if action.is_empty() {
+        println!(
+            "{} {} is frozen; nothing recorded",
+            character.name, character.streaks[s_idx].name
+        );
+        return Ok(());
+    }
+    crate::log_result(dir, character, s_idx, is_win, "", "");
+    crate::save_data(dir, characters)?;
+
+    let streak = &character.streaks[s_idx];
+    println!(
+        "{} {}: {} (PB {})",
+        character.name, streak.name, streak.current, streak.best
+    );
+    Ok(())
+}
+
+/// Print where two saves differ, and which has the higher best of each category.
+fn compare(first: &Path, second: &Path, merged: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let ours = merge::read(first)?;
+    let theirs = merge::read(second)?;
+    let differences = merge::differences(&ours, &theirs);
+
+    let mut out = io::stdout().lock();
+    if differences.is_empty() {
+        writeln!(out, "No differences")?;
+    } else {
+        let name_width = differences
+            .iter()
+            .map(|d| d.character.chars().count())
+            .chain(std::iter::once("Character".len()))
+            .max()
+            .unwrap_or(0);
+        let category_width = differences
+            .iter()
+            .map(|d| d.category.chars().count())
+            .chain(std::iter::once("Category".len()))
+            .max()
+            .unwrap_or(0);
+        // Each side as "current / best", or "-" where that save lacks the category.
+        let side = |entry: Option<(i32, i32)>| {
+            entry.map_or("-".to_string(), |(current, best)| {
+                format!("{} / {}", current, best)
+            })
+        };
+        writeln!(
+            out,
+            "{:<name_width$}  {:<category_width$}  {:>13}  {:>13}  Higher best",
+            "Character", "Category", "First", "Second"
+        )?;
+        for d in &differences {
+            let higher = match (d.ours, d.theirs) {
+                (Some((_, a)), Some((_, b))) if a > b => "first",
+                (Some((_, a)), Some((_, b))) if b > a => "second",
+                (Some(_), Some(_)) => "same",
+                (Some(_), None) => "first only",
+                (None, _) => "second only",
+            };
+            writeln!(
+                out,
+                "{:<name_width$}  {:<category_width$}  {:>13}  {:>13}  {}",
+                d.character,
+                d.category,
+                side(d.ours),
+                side(d.theirs),
+                higher
+            )?;
+        }
+    }
+
+    if let Some(path) = merged {
+        merge::write(path, &merge::merge(&ours, &theirs, merge::Current::Ours))?;
+        writeln!(out, "Wrote {}", path.display())?;
+    }
+    Ok(())
+}
+
+/// Print `characters` as a table of current/best per category, or as JSON.
+fn print_stats(characters: &[Character], json: bool) -> Result<(), Box<dyn Error>> {
+    let mut out = io::stdout().lock();
+    if json {
+        writeln!(out, "{}", serde_json::to_string_pretty(characters)?)?;
+        return Ok(());
+    }
+
+    let name_width = characters
+        .iter()
+        .map(|c| c.name.chars().count())
+        .chain(std::iter::once("Character".len()))
+        .max()
+        .unwrap_or(0);
+    let category_width = characters
+        .iter()
+        .flat_map(|c| &c.streaks)
+        .map(|s| s.name.chars().count())
+        .chain(std::iter::once("Category".len()))
+        .max()
+        .unwrap_or(0);
+
+    writeln!(
+        out,
+        "{:<name_width$}  {:<category_width$}  {:>7}  {:>4}",
+        "Character", "Category", "Current", "Best"
+    )?;
+    for character in characters {
+        for streak in &character.streaks {
+            writeln!(
+                out,
+                "{:<name_width$}  {:<category_width$}  {:>7}  {:>4}",
+                character.name, streak.name, streak.current, streak.best
+            )?;
+        }
+    }
+    Ok(())
+}
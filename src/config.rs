@@ -0,0 +1,243 @@
+//! User preferences stored in `config.toml`. Every field has a default, so keys
+//! missing from the file are filled in rather than rejected.
+use crate::{
+    autosave::AutosaveConfig, discord::DiscordConfig, http::HttpConfig, killer_list::SortMode,
+    overlay::OverlayConfig, portraits::PortraitConfig, sound::SoundConfig, twitch::TwitchConfig,
+    update::UpdateConfig,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub const CONFIG_FILE: &str = "config.toml";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Config {
+    /// Profile loaded at startup; empty means the default profile.
+    pub profile: String,
+    /// Folder holding the saves and profiles, relative to the data folder. Empty keeps
+    /// them in it.
+    pub data_dir: String,
+    /// Folder holding `media/` (and `aliases.txt`), relative to the data folder. Empty
+    /// uses the data folder itself.
+    pub media_root: String,
+    pub theme: Theme,
+    /// UI language such as `de`; empty follows the system.
+    pub language: String,
+    /// Keep the window above other windows, e.g. the game on a second monitor.
+    pub always_on_top: bool,
+    /// Start the app when the user logs in. Only used on Windows.
+    pub start_with_windows: bool,
+    pub confirm_loss: ConfirmLossConfig,
+    pub milestones: MilestoneConfig,
+    pub buttons: ButtonConfig,
+    pub counter: CounterConfig,
+    pub autosave: AutosaveConfig,
+    pub killer_sort: SortMode,
+    pub hotkeys: HotkeyConfig,
+    pub overlay: OverlayConfig,
+    pub sound: SoundConfig,
+    pub http: HttpConfig,
+    pub discord: DiscordConfig,
+    pub twitch: TwitchConfig,
+    pub portraits: PortraitConfig,
+    pub updates: UpdateConfig,
+    /// Where the window was last left; missing until it's first moved or resized.
+    pub window: Option<WindowGeometry>,
+}
+
+impl Config {
+    /// Where the saves and profiles live, given the data folder `base`.
+    pub fn data_root(&self, base: &Path) -> PathBuf {
+        if self.data_dir.is_empty() {
+            base.to_path_buf()
+        } else {
+            base.join(&self.data_dir)
+        }
+    }
+
+    /// The folder `media/` is in, given the data folder `base`. Portrait paths in the
+    /// saves are relative to it.
+    pub fn media_root(&self, base: &Path) -> PathBuf {
+        if self.media_root.is_empty() {
+            base.to_path_buf()
+        } else {
+            base.join(&self.media_root)
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+/// Outer top-left corner and inner size of the main window, in physical pixels.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Global shortcuts, written like `F7` or `Ctrl+Shift+KeyW`. Empty disables a key.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct HotkeyConfig {
+    pub win: String,
+    pub loss: String,
+}
+
+impl Default for HotkeyConfig {
+    fn default() -> Self {
+        Self {
+            win: "F7".into(),
+            loss: "F8".into(),
+        }
+    }
+}
+
+/// Text of the win and loss buttons, per role of the selected character.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ButtonConfig {
+    pub killer_win: String,
+    pub killer_loss: String,
+    pub survivor_win: String,
+    pub survivor_loss: String,
+    /// Put the loss button before the win button.
+    pub swap: bool,
+}
+
+impl Default for ButtonConfig {
+    fn default() -> Self {
+        Self {
+            killer_win: "I Won".into(),
+            killer_loss: "I Lost".into(),
+            survivor_win: "Escaped".into(),
+            survivor_loss: "Died".into(),
+            swap: false,
+        }
+    }
+}
+
+/// Asking before a loss from the window resets a streak longer than `threshold`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ConfirmLossConfig {
+    pub enabled: bool,
+    pub threshold: i32,
+}
+
+impl Default for ConfirmLossConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold: 5,
+        }
+    }
+}
+
+impl ConfirmLossConfig {
+    /// Whether a loss that would end a streak of `current` has to be confirmed first.
+    pub fn needed(&self, current: i32) -> bool {
+        self.enabled && current > self.threshold
+    }
+}
+
+/// A short message each time a streak reaches a multiple of `every` wins.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct MilestoneConfig {
+    pub enabled: bool,
+    pub every: i32,
+    /// Also play the PB sound, if sounds are on.
+    pub sound: bool,
+}
+
+impl Default for MilestoneConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            every: 5,
+            sound: false,
+        }
+    }
+}
+
+impl MilestoneConfig {
+    /// Whether a win that brought a streak to `current` reached a milestone.
+    pub fn reached(&self, current: i32) -> bool {
+        self.enabled && self.every > 0 && current > 0 && current % self.every == 0
+    }
+}
+
+/// Bounds every current streak is kept within, whichever way it changes. No `max`
+/// means no cap.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct CounterConfig {
+    pub min: i32,
+    pub max: Option<i32>,
+    /// A single result within this many milliseconds of the last one is taken for a
+    /// double trigger and ignored; 0 turns this off. Batches of wins are never held up.
+    pub cooldown_ms: u64,
+    /// Let wins still count on a frozen category; losses never do.
+    pub frozen_wins: bool,
+}
+
+impl Default for CounterConfig {
+    fn default() -> Self {
+        Self {
+            min: 0,
+            max: None,
+            cooldown_ms: 150,
+            frozen_wins: false,
+        }
+    }
+}
+
+impl CounterConfig {
+    pub fn clamp(&self, value: i32) -> i32 {
+        let value = value.max(self.min);
+        self.max.map_or(value, |max| value.min(max.max(self.min)))
+    }
+}
+
+/// Load the config in `base`, creating it with defaults if it doesn't exist yet. A
+/// file that fails to parse is copied to `config.toml.bak` first, since the next
+/// setting change writes the defaults over it.
+pub fn load(base: &Path) -> Config {
+    let path = base.join(CONFIG_FILE);
+    match fs::read_to_string(&path) {
+        Ok(text) => toml::from_str(&text).unwrap_or_else(|e| {
+            log::warn!("Could not parse {}: {}", CONFIG_FILE, e);
+            let backup = path.with_extension("toml.bak");
+            match fs::copy(&path, &backup) {
+                Ok(_) => log::warn!("Kept the broken file as {}", backup.display()),
+                Err(e) => log::warn!("Could not back up {}: {}", CONFIG_FILE, e),
+            }
+            Config::default()
+        }),
+        Err(_) => {
+            let config = Config::default();
+            if let Err(e) = save(base, &config) {
+                log::warn!("Could not create {}: {}", CONFIG_FILE, e);
+            }
+            config
+        }
+    }
+}
+
+pub fn save(base: &Path, config: &Config) -> Result<(), Box<dyn Error>> {
+    fs::write(base.join(CONFIG_FILE), toml::to_string_pretty(config)?)?;
+    Ok(())
+}
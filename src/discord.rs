@@ -0,0 +1,83 @@
+//! Discord Rich Presence for the selected streak, e.g. "The Nurse" / "4k streak: 8"
+//! under the application's name. Does nothing while Discord isn't running.
+use crate::Character;
+use discord_rich_presence::{activity::Activity, DiscordIpc, DiscordIpcClient};
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::{Cell, RefCell},
+    time::{Duration, Instant},
+};
+
+/// How long to wait before looking for Discord again after it wasn't running.
+const RETRY_AFTER: Duration = Duration::from_secs(30);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct DiscordConfig {
+    pub enabled: bool,
+    /// Application ID from the Discord developer portal. Discord shows the
+    /// application's name after "Playing", so name it e.g. "DBD".
+    pub application_id: String,
+}
+
+/// The connection to the local Discord client, made on the first update.
+#[derive(Default)]
+pub struct Presence {
+    /// The client and the application ID it was opened with.
+    client: RefCell<Option<(String, DiscordIpcClient)>>,
+    last_attempt: Cell<Option<Instant>>,
+}
+
+impl Presence {
+    /// Show `character`'s category `streak_idx` as the status, or clear it if
+    /// `config` turns the presence off.
+    pub fn update(&self, config: &DiscordConfig, character: Option<&Character>, streak_idx: usize) {
+        let mut client = self.client.borrow_mut();
+        if client
+            .as_ref()
+            .is_some_and(|(id, _)| !config.enabled || *id != config.application_id)
+        {
+            if let Some((_, mut old)) = client.take() {
+                let _ = old.clear_activity();
+                let _ = old.close();
+            }
+        }
+        if !config.enabled || config.application_id.is_empty() {
+            return;
+        }
+        let Some((character, cat)) =
+            character.and_then(|c| c.streaks.get(streak_idx).map(|cat| (c, cat)))
+        else {
+            return;
+        };
+
+        if client.is_none() {
+            if self
+                .last_attempt
+                .get()
+                .is_some_and(|at| at.elapsed() < RETRY_AFTER)
+            {
+                return;
+            }
+            self.last_attempt.set(Some(Instant::now()));
+            let mut new = DiscordIpcClient::new(&config.application_id);
+            if let Err(e) = new.connect() {
+                log::debug!("Discord isn't reachable: {}", e);
+                return;
+            }
+            *client = Some((config.application_id.clone(), new));
+        }
+
+        let state = format!("{} streak: {}", cat.name, cat.current);
+        let activity = Activity::new()
+            .details(character.name.as_str())
+            .state(state.as_str());
+        if let Some((_, ipc)) = client.as_mut() {
+            if let Err(e) = ipc.set_activity(activity) {
+                // Most likely Discord was closed; reconnect on a later update.
+                log::debug!("Could not update Discord status: {}", e);
+                *client = None;
+            }
+        }
+    }
+}
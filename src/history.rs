@@ -0,0 +1,83 @@
+//! Append-only log of every recorded match, one JSON object per line.
+use crate::StreakCategory;
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::Path,
+};
+
+pub const HISTORY_FILE: &str = "history.jsonl";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HistoryEntry {
+    pub character: String,
+    pub category: String,
+    pub win: bool,
+    /// The category's `current` value after the result was applied.
+    pub current: i32,
+    /// RFC 3339 local time the result was recorded.
+    pub timestamp: String,
+    /// Map the match was played on, if the player typed one in.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub map: String,
+    /// Build or loadout tag, if any.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub build: String,
+}
+
+impl HistoryEntry {
+    pub fn new(character: &str, category: &StreakCategory, win: bool) -> Self {
+        Self {
+            character: character.to_string(),
+            category: category.name.clone(),
+            win,
+            current: category.current,
+            timestamp: chrono::Local::now().to_rfc3339(),
+            map: String::new(),
+            build: String::new(),
+        }
+    }
+
+    /// The same entry, tagged with the match's map and build.
+    pub fn with_match(mut self, map: &str, build: &str) -> Self {
+        self.map = map.to_string();
+        self.build = build.to_string();
+        self
+    }
+}
+
+/// Append one entry to the history file in `dir`, creating it if it doesn't exist yet.
+pub fn append_entry(dir: &Path, entry: &HistoryEntry) -> Result<(), Box<dyn Error>> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(HISTORY_FILE))?;
+    let line = serde_json::to_string(entry)?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+/// Every entry in the history file in `dir`, oldest first. A missing file is an empty
+/// history, and lines that don't parse are skipped.
+pub fn load(dir: &Path) -> Vec<HistoryEntry> {
+    let path = dir.join(HISTORY_FILE);
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) => {
+            if e.kind() != ErrorKind::NotFound {
+                log::warn!("Could not read {}: {}", path.display(), e);
+            }
+            return Vec::new();
+        }
+    };
+    text.lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|line| {
+            serde_json::from_str(line)
+                .map_err(|e| log::warn!("Skipping history line {:?}: {}", line, e))
+                .ok()
+        })
+        .collect()
+}
@@ -0,0 +1,63 @@
+//! System-wide shortcuts for recording results while the game has focus.
+use crate::config::HotkeyConfig;
+use global_hotkey::{hotkey::HotKey, GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+
+pub enum HotkeyAction {
+    Win,
+    Loss,
+}
+
+pub struct Hotkeys {
+    // Dropping the manager unregisters every key.
+    _manager: GlobalHotKeyManager,
+    win: Option<u32>,
+    loss: Option<u32>,
+}
+
+impl Hotkeys {
+    /// Register the configured keys. A key that can't be parsed or is already taken
+    /// by another program is skipped with a warning.
+    pub fn register(config: &HotkeyConfig) -> Option<Self> {
+        let manager = GlobalHotKeyManager::new()
+            .map_err(|e| log::warn!("Global hotkeys unavailable: {}", e))
+            .ok()?;
+        let register = |key: &str| -> Option<u32> {
+            if key.trim().is_empty() {
+                return None;
+            }
+            let hotkey: HotKey = key
+                .parse()
+                .map_err(|e| log::warn!("Invalid hotkey {:?}: {}", key, e))
+                .ok()?;
+            manager
+                .register(hotkey)
+                .map_err(|e| log::warn!("Could not register hotkey {}: {}", key, e))
+                .ok()?;
+            Some(hotkey.id())
+        };
+        let win = register(&config.win);
+        let loss = register(&config.loss);
+        Some(Self {
+            _manager: manager,
+            win,
+            loss,
+        })
+    }
+
+    /// Drain pending key presses.
+    pub fn poll(&self) -> Vec<HotkeyAction> {
+        GlobalHotKeyEvent::receiver()
+            .try_iter()
+            .filter(|e| e.state == HotKeyState::Pressed)
+            .filter_map(|e| {
+                if Some(e.id) == self.win {
+                    Some(HotkeyAction::Win)
+                } else if Some(e.id) == self.loss {
+                    Some(HotkeyAction::Loss)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,108 @@
+//! Local HTTP server for OBS browser sources. `GET /streaks` returns every character
+//! and `GET /current` the selected streak, both as JSON. `/current` is published
+//! along with the overlay files, so the two always show the same streak.
+use crate::{lock, Character, StreakCategory};
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct HttpConfig {
+    pub enabled: bool,
+    /// Port on `127.0.0.1` to listen on.
+    pub port: u16,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 8787,
+        }
+    }
+}
+
+/// Body of `/current`.
+#[derive(Serialize)]
+struct Current<'a> {
+    character: &'a str,
+    #[serde(flatten)]
+    streak: &'a StreakCategory,
+}
+
+/// The body `/current` answers with, replaced on every [`Publisher::publish`].
+#[derive(Clone)]
+pub struct Publisher {
+    current: Arc<Mutex<String>>,
+}
+
+impl Default for Publisher {
+    fn default() -> Self {
+        Self {
+            current: Arc::new(Mutex::new("null".into())),
+        }
+    }
+}
+
+impl Publisher {
+    /// Serve `character`'s category `streak_idx` from `/current` from now on, or
+    /// `null` if there's no such streak.
+    pub fn publish(&self, character: Option<&Character>, streak_idx: usize) {
+        let current = character.and_then(|c| {
+            c.streaks.get(streak_idx).map(|streak| Current {
+                character: &c.name,
+                streak,
+            })
+        });
+        match serde_json::to_string(&current) {
+            Ok(body) => *lock(&self.current) = body,
+            Err(e) => log::warn!("Could not serialize the current streak: {}", e),
+        }
+    }
+}
+
+/// Start listening in a background thread. `/streaks` reads the shared characters
+/// directly; `/current` answers with whatever was last published. Failing to bind is
+/// a warning.
+pub fn start(config: &HttpConfig, characters: Arc<Mutex<Vec<Character>>>, current: Publisher) {
+    let server = match tiny_http::Server::http(("127.0.0.1", config.port)) {
+        Ok(server) => server,
+        Err(e) => {
+            log::warn!("Could not start HTTP server on {}: {}", config.port, e);
+            return;
+        }
+    };
+    log::info!("Serving streaks on http://127.0.0.1:{}", config.port);
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let path = request.url().split('?').next().unwrap_or_default();
+            let body = match path {
+                "/streaks" => serde_json::to_string(&*lock(&characters)),
+                "/current" => Ok(lock(&current.current).clone()),
+                _ => {
+                    let _ = request.respond(tiny_http::Response::empty(404));
+                    continue;
+                }
+            };
+            let response = match body {
+                Ok(body) => tiny_http::Response::from_string(body)
+                    .with_header(header("Content-Type", "application/json"))
+                    .with_header(header("Access-Control-Allow-Origin", "*")),
+                Err(e) => {
+                    log::warn!("Could not serialize HTTP response: {}", e);
+                    tiny_http::Response::from_string("").with_status_code(500)
+                }
+            };
+            if let Err(e) = request.respond(response) {
+                log::warn!("Could not answer HTTP request: {}", e);
+            }
+        }
+    });
+}
+
+fn header(name: &str, value: &str) -> tiny_http::Header {
+    tiny_http::Header::from_bytes(name, value).expect("header is valid ASCII")
+}
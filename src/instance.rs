@@ -0,0 +1,48 @@
+//! Keeps a second copy of the app off the same data folder, where the two would
+//! overwrite each other's saves.
+use std::{
+    fs::{File, OpenOptions, TryLockError},
+    path::Path,
+};
+
+pub const LOCK_FILE: &str = "dbd-winstreak.lock";
+
+/// Held for as long as the app runs; the OS also drops the lock if the process dies.
+pub struct InstanceLock {
+    // `None` if the lock file couldn't be used, which only loses the protection.
+    file: Option<File>,
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        if let Some(Err(e)) = self.file.as_ref().map(File::unlock) {
+            log::warn!("Could not release {}: {}", LOCK_FILE, e);
+        }
+    }
+}
+
+/// Lock `base`, or `None` if another instance already has it. This runs before logging
+/// starts (which would rotate the other instance's log), so problems go to stderr.
+pub fn acquire(base: &Path) -> Option<InstanceLock> {
+    let path = base.join(LOCK_FILE);
+    let file = match OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+    {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("Could not open {}: {}", path.display(), e);
+            return Some(InstanceLock { file: None });
+        }
+    };
+    match file.try_lock() {
+        Ok(()) => Some(InstanceLock { file: Some(file) }),
+        Err(TryLockError::WouldBlock) => None,
+        Err(TryLockError::Error(e)) => {
+            eprintln!("Could not lock {}: {}", path.display(), e);
+            Some(InstanceLock { file: None })
+        }
+    }
+}
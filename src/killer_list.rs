@@ -0,0 +1,115 @@
+//! The killer dropdown's view over `characters`. The dropdown can show a filtered
+//! subset, so its indices differ from the ones used to look up a `Character`.
+use crate::{AppWindow, Character};
+use serde::{Deserialize, Serialize};
+use slint::VecModel;
+use std::{cmp::Reverse, collections::HashMap, rc::Rc};
+
+/// Order of the killer dropdown, after favorites. `characters` itself always stays
+/// sorted by name.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortMode {
+    #[default]
+    Name,
+    /// Highest `best` across any category first.
+    Best,
+    /// Highest `current` across any category first.
+    Current,
+}
+
+impl SortMode {
+    pub const ALL: [SortMode; 3] = [SortMode::Name, SortMode::Best, SortMode::Current];
+}
+
+#[derive(Default)]
+pub struct KillerList {
+    /// Case-insensitive text a name must contain to be shown.
+    pub filter: String,
+    /// Also list characters marked as hidden.
+    pub show_hidden: bool,
+    pub sort: SortMode,
+    /// Index into `characters` for each displayed entry.
+    order: Vec<usize>,
+    /// Index into `characters` for every name, shown or not.
+    by_name: HashMap<String, usize>,
+}
+
+impl KillerList {
+    pub fn new(sort: SortMode) -> Self {
+        Self {
+            sort,
+            ..Default::default()
+        }
+    }
+
+    /// Recompute which characters are displayed and where each name is. Must be called
+    /// whenever characters are added, renamed or re-sorted.
+    pub fn rebuild(&mut self, characters: &[Character]) {
+        self.by_name = characters
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (c.name.clone(), i))
+            .collect();
+
+        let filter = self.filter.to_lowercase();
+        self.order = characters
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| self.show_hidden || !c.hidden)
+            .filter(|(_, c)| c.name.to_lowercase().contains(&filter))
+            .map(|(i, _)| i)
+            .collect();
+
+        // Stable sorts, so ties keep the alphabetical order of `characters`.
+        let max_of = |i: usize, f: fn(&crate::StreakCategory) -> i32| {
+            characters[i].streaks.iter().map(f).max().unwrap_or(0)
+        };
+        match self.sort {
+            SortMode::Name => {}
+            SortMode::Best => self.order.sort_by_key(|&i| Reverse(max_of(i, |s| s.best))),
+            SortMode::Current => self
+                .order
+                .sort_by_key(|&i| Reverse(max_of(i, |s| s.current))),
+        }
+        self.order.sort_by_key(|&i| !characters[i].favorite);
+    }
+
+    /// Push the displayed names to the dropdown and select `selected` if it's shown.
+    pub fn show(&self, ui: &AppWindow, characters: &[Character], selected: usize) {
+        let names: Vec<_> = self
+            .order
+            .iter()
+            .map(|&i| characters[i].name.clone().into())
+            .collect();
+        ui.set_killer_names(Rc::new(VecModel::from(names)).into());
+        self.select(ui, selected);
+    }
+
+    /// Index into `characters` of the character called `name`.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.by_name.get(name).copied()
+    }
+
+    /// The character shown after (or before) `current`, wrapping around at the ends.
+    /// If `current` is filtered out, starts from the first (or last) one shown.
+    pub fn step(&self, current: usize, forward: bool) -> Option<usize> {
+        let len = self.order.len();
+        if len == 0 {
+            return None;
+        }
+        let pos = match self.order.iter().position(|&i| i == current) {
+            Some(pos) if forward => (pos + 1) % len,
+            Some(pos) => (pos + len - 1) % len,
+            None if forward => 0,
+            None => len - 1,
+        };
+        Some(self.order[pos])
+    }
+
+    /// Point the dropdown at a character, or at nothing if it's filtered out.
+    pub fn select(&self, ui: &AppWindow, selected: usize) {
+        let shown = self.order.iter().position(|&i| i == selected);
+        ui.set_selected_killer_index(shown.map_or(-1, |i| i as i32));
+    }
+}
@@ -0,0 +1,52 @@
+//! Log output to `dbd-winstreak.log`, since release builds on Windows have no console.
+use std::{fs, path::Path};
+
+pub const LOG_FILE: &str = "dbd-winstreak.log";
+/// Size at which the log is rotated on startup.
+const LOG_MAX_BYTES: u64 = 1024 * 1024;
+/// Rotated logs kept as `dbd-winstreak.log.1` (newest) through `.N` (oldest).
+const LOG_KEEP: usize = 3;
+
+/// Start logging to the log file in `base`, and to stderr in debug builds.
+pub fn init(base: &Path) {
+    let path = base.join(LOG_FILE);
+    if let Err(e) = rotate(&path) {
+        eprintln!("Could not rotate {}: {}", LOG_FILE, e);
+    }
+
+    let mut dispatch = fern::Dispatch::new()
+        .format(|out, message, record| {
+            out.finish(format_args!(
+                "{} {:<5} {}",
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+                record.level(),
+                message
+            ))
+        })
+        .level(log::LevelFilter::Info);
+    match fern::log_file(&path) {
+        Ok(file) => dispatch = dispatch.chain(file),
+        Err(e) => eprintln!("Could not open {}: {}", LOG_FILE, e),
+    }
+    if cfg!(debug_assertions) {
+        dispatch = dispatch.chain(std::io::stderr());
+    }
+    if let Err(e) = dispatch.apply() {
+        eprintln!("Could not start logging: {}", e);
+    }
+}
+
+/// Shift `log` to `log.1`, `log.1` to `log.2` and so on once it grows too big.
+fn rotate(path: &Path) -> std::io::Result<()> {
+    if fs::metadata(path).map_or(true, |m| m.len() < LOG_MAX_BYTES) {
+        return Ok(());
+    }
+    let numbered = |n: usize| path.with_extension(format!("log.{}", n));
+    fs::remove_file(numbered(LOG_KEEP)).ok();
+    for n in (1..LOG_KEEP).rev() {
+        if numbered(n).exists() {
+            fs::rename(numbered(n), numbered(n + 1))?;
+        }
+    }
+    fs::rename(path, numbered(1))
+}
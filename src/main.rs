@@ -1,47 +1,402 @@
 // Prevent console window in release builds on Windows. Ignored on other platforms.
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+use clap::Parser;
 use serde::{Deserialize, Serialize};
-use slint::{Image, VecModel};
+use slint::{
+    winit_030::{winit, WinitWindowAccessor, WinitWindowEventResult},
+    Model, VecModel,
+};
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     error::Error,
     fs::{self, OpenOptions},
-    io::{BufRead, BufReader, BufWriter, Write},
-    path::Path,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
     rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    time::{Duration, Instant},
 };
 slint::include_modules!();
 
+mod aliases;
+mod archive;
+mod autosave;
+mod card;
+mod cli;
+mod config;
+mod discord;
+mod history;
+mod hotkeys;
+mod http;
+mod instance;
+mod killer_list;
+mod logging;
+mod map_stats;
+mod merge;
+mod numbers;
+mod overlay;
+mod paths;
+mod portraits;
+mod profile;
+mod promotions;
+mod report;
+mod schema;
+mod series;
+mod session;
+mod sound;
+mod startup;
+mod summary;
+mod tray;
+mod twitch;
+mod update;
+mod watch;
+
 /// Default streak categories for killers.
 static DEFAULT_KILLER_STREAK_CATEGORIES: &[&str] = &["4k", "3k", "Perkless 4k", "Perkless 3k"];
 /// Default streak categories for survivor.
 static DEFAULT_SURVIVOR_STREAK_CATEGORIES: &[&str] = &["Solo escape", "3 out"];
+/// Saved characters and streaks, relative to the profile directory.
+const STREAKS_FILE: &str = "streaks.json";
+/// Category list used for every killer.
+const KILLER_STREAKS_FILE: &str = "killer_streaks.txt";
+/// Category list used for survivor.
+const SURVIVOR_STREAKS_FILE: &str = "survivor_streaks.txt";
+/// Folder that holds timestamped copies of `streaks.json`.
+const BACKUP_DIR: &str = "backups";
+/// Changes listed before a merge is applied; the rest are only counted.
+const MERGE_SUMMARY_LINES: usize = 12;
+/// Number of backups kept before the oldest are deleted.
+const BACKUP_LIMIT: usize = 10;
+/// Portrait shown when a character's image is missing or can't be decoded.
+static PLACEHOLDER_PORTRAIT: &[u8] = include_bytes!("../ui/placeholder.svg");
+/// Folder scanned for character portraits.
+const MEDIA_DIR: &str = "media";
+/// Folder (anywhere under `media`) whose portraits are survivors.
+const SURVIVORS_DIR: &str = "survivors";
+/// Folder (anywhere under `media`) whose portraits are killers.
+const KILLERS_DIR: &str = "killers";
+/// Maximum number of actions kept on the undo stack.
+const UNDO_LIMIT: usize = 50;
+/// Most wins "Add wins" records in one go.
+const MAX_BATCH_WINS: i32 = 50;
+/// How long a milestone message stays up.
+const MILESTONE_TOAST: Duration = Duration::from_secs(3);
+/// How long the window has to stay put before its new geometry is saved, so dragging
+/// it around doesn't write the config on every step.
+const GEOMETRY_SAVE_DELAY: Duration = Duration::from_millis(500);
+/// How much of the window's top edge, in physical pixels, has to be on some monitor
+/// for it to count as on screen, so there's enough of the title bar left to drag.
+const ON_SCREEN_MIN: i32 = 64;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 struct StreakCategory {
     name: String,
     current: i32,
     best: i32,
+    #[serde(default)]
+    wins: i32,
+    #[serde(default)]
+    losses: i32,
+    /// ISO-8601 date the current `best` was set.
+    #[serde(default)]
+    best_date: Option<String>,
+    /// Streak the player is aiming for, if they've set one.
+    #[serde(default)]
+    goal: Option<i32>,
+    /// Longest run of consecutive losses.
+    #[serde(default)]
+    worst: i32,
+    /// Losses in a row since the last win, tracked to maintain `worst`.
+    #[serde(default)]
+    loss_run: i32,
+    /// Free text such as the build being run.
+    #[serde(default)]
+    note: String,
+    /// Accent as `#rrggbb`, used instead of the character's while this is selected.
+    #[serde(default)]
+    color: Option<String>,
+    /// Results are ignored (wins too, unless `frozen_wins` is set), so someone else can
+    /// play without touching the streak. Manual corrections still apply.
+    #[serde(default)]
+    frozen: bool,
+    /// `(wins, losses)` when this run of the app started. Runtime only.
+    #[serde(skip)]
+    session_start: (i32, i32),
+}
+
+impl StreakCategory {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            current: 0,
+            best: 0,
+            wins: 0,
+            losses: 0,
+            best_date: None,
+            goal: None,
+            worst: 0,
+            loss_run: 0,
+            note: String::new(),
+            color: None,
+            frozen: false,
+            session_start: (0, 0),
+        }
+    }
+
+    /// Raise `best` to `value` if it's higher, stamping today's date when it moves.
+    fn raise_best(&mut self, value: i32) {
+        if value > self.best {
+            self.best = value;
+            self.best_date = Some(chrono::Local::now().date_naive().to_string());
+        }
+    }
+
+    /// Set the current streak within `limits`, raising `best` to match. Every change
+    /// to `current` goes through here, so `best` can't pass the cap either.
+    fn set_current(&mut self, value: i32, limits: &config::CounterConfig) {
+        self.current = limits.clamp(value);
+        self.raise_best(self.current);
+    }
+
+    /// Percentage of recorded games that were wins, or 0 before any games.
+    fn win_rate(&self) -> f32 {
+        let games = self.wins + self.losses;
+        if games == 0 {
+            0.0
+        } else {
+            self.wins as f32 * 100.0 / games as f32
+        }
+    }
+
+    /// Start counting session results from the current totals.
+    fn start_session(&mut self) {
+        self.session_start = (self.wins, self.losses);
+    }
+
+    /// Wins and losses recorded since the session started.
+    fn session_delta(&self) -> (i32, i32) {
+        (
+            self.wins - self.session_start.0,
+            self.losses - self.session_start.1,
+        )
+    }
+
+    /// How far `current` is towards the goal, from 0 to 1, or 0 without a goal.
+    fn goal_progress(&self) -> f32 {
+        match self.goal {
+            Some(goal) if goal > 0 => (self.current as f32 / goal as f32).min(1.0),
+            _ => 0.0,
+        }
+    }
+}
+
+/// Which side a character plays, deciding its category list and whether killer-only
+/// rules such as promotions apply.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+enum Role {
+    #[default]
+    Killer,
+    Survivor,
+}
+
+impl Role {
+    /// Guess the role from a name, as every save before roles existed did.
+    fn from_name(name: &str) -> Self {
+        if name.eq_ignore_ascii_case("survivor") {
+            Role::Survivor
+        } else {
+            Role::Killer
+        }
+    }
+
+    /// Pick this role's list out of the `(killer, survivor)` categories.
+    fn categories<'a>(
+        self,
+        killer_cats: &'a [String],
+        survivor_cats: &'a [String],
+    ) -> &'a [String] {
+        match self {
+            Role::Killer => killer_cats,
+            Role::Survivor => survivor_cats,
+        }
+    }
+}
+
+/// Which part of a portrait is shown. `x` and `y` run from 0 to 1 and place the visible
+/// area within the image; `zoom` of 1 shows all of it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+struct ImageCrop {
+    x: f32,
+    y: f32,
+    zoom: f32,
+}
+
+impl Default for ImageCrop {
+    fn default() -> Self {
+        Self {
+            x: 0.5,
+            y: 0.5,
+            zoom: 1.0,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct Character {
     name: String,
     image_path: String,
+    /// Framing of the portrait; `None` shows the whole image.
+    #[serde(default)]
+    image_crop: Option<ImageCrop>,
+    #[serde(default)]
+    role: Role,
+    /// Listed before everyone else in the killer dropdown.
+    #[serde(default)]
+    favorite: bool,
+    /// Left out of the killer dropdown unless hidden characters are shown.
+    #[serde(default)]
+    hidden: bool,
+    /// Accent as `#rrggbb` that tints the panel while this character is selected.
+    #[serde(default)]
+    color: Option<String>,
+    /// Every game recorded for a survivor, whichever category it went to.
+    #[serde(default, skip_serializing_if = "SurvivorTotals::is_empty")]
+    survivor_totals: SurvivorTotals,
     streaks: Vec<StreakCategory>,
 }
 
-/// Load streak categories from a text file, falling back to defaults if needed.
-fn load_categories_from_file(path: &str, defaults: &[&str]) -> Vec<String> {
-    if let Ok(file) = std::fs::File::open(path) {
-        let reader = BufReader::new(file);
-        let categories: Vec<String> = reader
-            .lines()
-            .map_while(Result::ok)
-            .map(|l| l.trim().to_string())
-            .filter(|l| !l.is_empty() && !l.starts_with('#'))
-            .collect();
+/// A survivor's games and escapes across all categories and sessions. Unlike the
+/// streaks, nothing but recorded results (and undoing them) changes these.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(default)]
+struct SurvivorTotals {
+    games: i32,
+    escapes: i32,
+}
+
+impl SurvivorTotals {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn record(&mut self, escaped: bool) {
+        self.games += 1;
+        if escaped {
+            self.escapes += 1;
+        }
+    }
+
+    /// Percentage of games escaped, or 0 before the first game.
+    fn escape_rate(&self) -> f32 {
+        if self.games == 0 {
+            0.0
+        } else {
+            self.escapes as f32 * 100.0 / self.games as f32
+        }
+    }
+}
+
+/// The state of a single streak category before an action (or an undo) touched it.
+#[derive(Debug, Clone)]
+struct StreakChange {
+    char_idx: usize,
+    streak_idx: usize,
+    previous: StreakCategory,
+    /// The character's survivor totals before, when the action changed them.
+    totals: Option<SurvivorTotals>,
+}
+
+impl StreakChange {
+    fn capture(char_idx: usize, streak_idx: usize, cat: &StreakCategory) -> Self {
+        Self {
+            char_idx,
+            streak_idx,
+            previous: cat.clone(),
+            totals: None,
+        }
+    }
+}
+
+/// Every category change made by one win/loss, undone together.
+type UndoAction = Vec<StreakChange>;
+
+/// Drop undo history for a removed category and shift later categories down to match.
+fn forget_category(stack: &mut Vec<UndoAction>, char_idx: usize, streak_idx: usize) {
+    for action in stack.iter_mut() {
+        action.retain(|c| !(c.char_idx == char_idx && c.streak_idx == streak_idx));
+        for change in action.iter_mut() {
+            if change.char_idx == char_idx && change.streak_idx > streak_idx {
+                change.streak_idx -= 1;
+            }
+        }
+    }
+    stack.retain(|a| !a.is_empty());
+}
 
+/// Drop undo history for a deleted character and shift later characters down to match.
+fn forget_character(stack: &mut Vec<UndoAction>, char_idx: usize) {
+    for action in stack.iter_mut() {
+        action.retain(|c| c.char_idx != char_idx);
+        for change in action.iter_mut() {
+            if change.char_idx > char_idx {
+                change.char_idx -= 1;
+            }
+        }
+    }
+    stack.retain(|a| !a.is_empty());
+}
+
+/// Keep undo history attached to two categories that swapped places.
+fn swap_category(stack: &mut [UndoAction], char_idx: usize, a: usize, b: usize) {
+    for change in stack.iter_mut().flatten() {
+        if change.char_idx == char_idx {
+            if change.streak_idx == a {
+                change.streak_idx = b;
+            } else if change.streak_idx == b {
+                change.streak_idx = a;
+            }
+        }
+    }
+}
+
+/// Restores each category in `action` and returns the changes that reverse the restore.
+fn apply_action(characters: &mut [Character], action: &UndoAction) -> UndoAction {
+    let mut inverse = UndoAction::new();
+    for change in action.iter().rev() {
+        let Some(character) = characters.get_mut(change.char_idx) else {
+            continue;
+        };
+        let totals = change
+            .totals
+            .map(|totals| std::mem::replace(&mut character.survivor_totals, totals));
+        if let Some(cat) = character.streaks.get_mut(change.streak_idx) {
+            inverse.push(StreakChange {
+                totals,
+                ..StreakChange::capture(change.char_idx, change.streak_idx, cat)
+            });
+            // The session baseline, note and color aren't part of the history, so keep
+            // the live ones.
+            let session_start = cat.session_start;
+            let note = std::mem::take(&mut cat.note);
+            let color = cat.color.take();
+            *cat = change.previous.clone();
+            cat.session_start = session_start;
+            cat.note = note;
+            cat.color = color;
+        }
+    }
+    inverse.reverse();
+    inverse
+}
+
+/// Load streak categories from a text file, falling back to defaults if needed.
+fn load_categories_from_file(path: &Path, defaults: &[&str]) -> Vec<String> {
+    if let Ok(bytes) = fs::read(path) {
+        let categories = parse_categories(&bytes);
         if !categories.is_empty() {
             return categories;
         }
@@ -49,14 +404,60 @@ fn load_categories_from_file(path: &str, defaults: &[&str]) -> Vec<String> {
 
     // File doesn't exist or is empty, create it with defaults
     if let Err(e) = create_default_streaks_file(path, defaults) {
-        eprintln!("Warning: Could not create {}: {}", path, e);
+        log::warn!("Could not create {}: {}", path.display(), e);
     }
 
     defaults.iter().map(|&s| s.to_string()).collect()
 }
 
+/// The categories listed in a category file's contents.
+fn parse_categories(bytes: &[u8]) -> Vec<String> {
+    // Editors differ in what they save: Notepad may add a BOM, and anything that isn't
+    // UTF-8 is read as best we can rather than dropped.
+    let text = String::from_utf8_lossy(bytes);
+    text.strip_prefix('\u{feff}')
+        .unwrap_or(&text)
+        .split(['\n', '\r'])
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .collect()
+}
+
+/// Add the `categories` a category file doesn't list yet to its end, keeping its
+/// comments, order and line endings. Returns how many lines were added.
+fn append_categories_to_file(path: &Path, categories: &[String]) -> Result<usize, Box<dyn Error>> {
+    let existing = fs::read(path)?;
+    let mut known = parse_categories(&existing);
+    let newline = if existing.windows(2).any(|w| w == b"\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut text = String::new();
+    let mut added = 0;
+    for category in categories {
+        if !known.contains(category) {
+            text.push_str(category);
+            text.push_str(newline);
+            known.push(category.clone());
+            added += 1;
+        }
+    }
+    if added == 0 {
+        return Ok(0);
+    }
+    if !existing.is_empty() && !existing.ends_with(b"\n") && !existing.ends_with(b"\r") {
+        text.insert_str(0, newline);
+    }
+    OpenOptions::new()
+        .append(true)
+        .open(path)?
+        .write_all(text.as_bytes())?;
+    Ok(added)
+}
+
 /// Create a default streaks text file with comments explaining how to use it.
-fn create_default_streaks_file(path: &str, defaults: &[&str]) -> Result<(), Box<dyn Error>> {
+fn create_default_streaks_file(path: &Path, defaults: &[&str]) -> Result<(), Box<dyn Error>> {
     let mut file = std::fs::File::create(path)?;
     writeln!(file, "# Streak Categories Configuration")?;
     writeln!(
@@ -81,257 +482,3515 @@ fn ensure_categories(character: &mut Character, categories: &[String]) -> bool {
     let mut changed = false;
     for cat_name in categories {
         if !character.streaks.iter().any(|s| s.name == *cat_name) {
-            character.streaks.push(StreakCategory {
-                name: cat_name.clone(),
-                current: 0,
-                best: 0,
-            });
+            character
+                .streaks
+                .push(StreakCategory::new(cat_name.clone()));
             changed = true;
         }
     }
     changed
 }
 
-fn format_name(stem: &str) -> String {
-    stem.replace('_', " ")
-        .chars()
-        .fold(String::new(), |mut acc, c| {
-            if c.is_uppercase() && acc.chars().last().is_some_and(|p| p.is_lowercase()) {
-                acc.push(' ');
-            }
-            acc.push(c);
-            acc
+/// Zero every current streak across all characters, keeping bests. Returns the changes
+/// so the reset can be undone.
+fn reset_all_current(characters: &mut [Character], limits: &config::CounterConfig) -> UndoAction {
+    characters
+        .iter_mut()
+        .enumerate()
+        .flat_map(|(char_idx, character)| reset_current(character, char_idx, limits))
+        .collect()
+}
+
+/// Zero every current streak of the character at `char_idx`, keeping bests.
+fn reset_current(
+    character: &mut Character,
+    char_idx: usize,
+    limits: &config::CounterConfig,
+) -> UndoAction {
+    let mut action = UndoAction::new();
+    let reset = limits.clamp(0);
+    for (streak_idx, cat) in character.streaks.iter_mut().enumerate() {
+        if cat.current != reset {
+            action.push(StreakChange::capture(char_idx, streak_idx, cat));
+            cat.set_current(reset, limits);
+        }
+    }
+    action
+}
+
+/// Apply a win or loss to one category within `limits`, raising bests and applying
+/// the promotion `rules` for killers. Returns the changes for undo and whether a new
+/// PB was set.
+fn record_result(
+    characters: &mut [Character],
+    rules: &[promotions::Rule],
+    limits: &config::CounterConfig,
+    char_idx: usize,
+    s_idx: usize,
+    is_win: bool,
+) -> (UndoAction, bool) {
+    let mut action = UndoAction::new();
+    let mut new_pb = false;
+    let Some(character) = characters.get_mut(char_idx) else {
+        return (action, new_pb);
+    };
+    if let Some(cat) = character.streaks.get_mut(s_idx) {
+        if cat.frozen && !(is_win && limits.frozen_wins) {
+            return (action, new_pb);
+        }
+        action.push(StreakChange::capture(char_idx, s_idx, cat));
+        let best = cat.best;
+        if is_win {
+            cat.set_current(cat.current.saturating_add(1), limits);
+            cat.wins += 1;
+            cat.loss_run = 0;
+        } else {
+            cat.set_current(0, limits);
+            cat.losses += 1;
+            cat.loss_run += 1;
+            cat.worst = cat.worst.max(cat.loss_run);
+        }
+        new_pb = cat.best > best;
+    }
+
+    match character.role {
+        // Promotions are killer-specific and should not run for survivors.
+        Role::Killer if is_win => apply_promotions(character, rules, &mut action, char_idx, s_idx),
+        Role::Killer => {}
+        Role::Survivor => {
+            if let Some(first) = action.first_mut() {
+                first.totals = Some(character.survivor_totals);
+                character.survivor_totals.record(is_win);
+            }
+        }
+    }
+    (action, new_pb)
+}
+
+/// Raise the best of every category a rule promotes `character`'s wins into.
+fn apply_promotions(
+    character: &mut Character,
+    rules: &[promotions::Rule],
+    action: &mut UndoAction,
+    char_idx: usize,
+    s_idx: usize,
+) {
+    for rule in rules {
+        let Some(best) = character
+            .streaks
+            .iter()
+            .find(|s| s.name == rule.from)
+            .map(|s| s.best)
+        else {
+            continue;
+        };
+        if let Some((to_idx, to_streak)) = character
+            .streaks
+            .iter_mut()
+            .enumerate()
+            .find(|(_, s)| s.name == rule.to)
+        {
+            if to_idx != s_idx && !action.iter().any(|c| c.streak_idx == to_idx) {
+                action.push(StreakChange::capture(char_idx, to_idx, to_streak));
+            }
+            to_streak.raise_best(best);
+        }
+    }
+}
+
+/// Append the result just recorded for `character`'s category to the history log.
+fn log_result(
+    dir: &Path,
+    character: &Character,
+    s_idx: usize,
+    is_win: bool,
+    map: &str,
+    build: &str,
+) {
+    if let Some(cat) = character.streaks.get(s_idx) {
+        let entry = history::HistoryEntry::new(&character.name, cat, is_win).with_match(map, build);
+        if let Err(e) = history::append_entry(dir, &entry) {
+            log::warn!("Could not write {}: {}", history::HISTORY_FILE, e);
+        }
+    }
+}
+
+/// Run `ensure_categories` over every character with its role's category list.
+fn ensure_all_categories(
+    characters: &mut [Character],
+    killer_cats: &[String],
+    survivor_cats: &[String],
+) -> bool {
+    let mut changed = false;
+    for character in characters {
+        let cats_to_use = character.role.categories(killer_cats, survivor_cats);
+        if ensure_categories(character, cats_to_use) {
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Positions of the categories `character` has that its role's category file doesn't
+/// list, e.g. after a line was deleted from the file.
+fn unlisted_categories(
+    character: &Character,
+    killer_cats: &[String],
+    survivor_cats: &[String],
+) -> Vec<usize> {
+    let listed = character.role.categories(killer_cats, survivor_cats);
+    (0..character.streaks.len())
+        .filter(|&i| !listed.contains(&character.streaks[i].name))
+        .collect()
+}
+
+/// One line per character with unlisted categories, such as
+/// `The Nurse: Perkless 4k (best 12)`, so a big best isn't deleted unnoticed.
+fn describe_unlisted(
+    characters: &[Character],
+    killer_cats: &[String],
+    survivor_cats: &[String],
+) -> Vec<String> {
+    characters
+        .iter()
+        .filter_map(|character| {
+            let unlisted = unlisted_categories(character, killer_cats, survivor_cats);
+            if unlisted.is_empty() {
+                return None;
+            }
+            let cats: Vec<String> = unlisted
+                .iter()
+                .map(|&i| {
+                    let cat = &character.streaks[i];
+                    format!("{} (best {})", cat.name, cat.best)
+                })
+                .collect();
+            Some(format!("{}: {}", character.name, cats.join(", ")))
         })
+        .collect()
+}
+
+/// Turn a file stem into a display name: underscores become spaces, and words are
+/// split where a capital follows a lowercase letter, a digit follows a letter, or a
+/// capital follows a digit. Runs of capitals stay together as acronyms or Roman
+/// numerals; when one runs into a lowercase word, a run of two (`PHthe`) ends before
+/// it, while a longer run (`PHThe`) gives its last capital to the word. So
+/// `TheTrapper` becomes `The Trapper`, `Nemesis2` becomes `Nemesis 2`, `TrapperIII`
+/// becomes `Trapper III` and `PHtheExecutioner` becomes `PH the Executioner`.
+fn format_name(stem: &str) -> String {
+    let chars: Vec<char> = stem.replace('_', " ").chars().collect();
+    let mut name = String::new();
+    // Capitals in a row just before the current character.
+    let mut caps = 0;
+    for (i, &c) in chars.iter().enumerate() {
+        let split = i.checked_sub(1).is_some_and(|prev| {
+            let p = chars[prev];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            (c.is_uppercase() && p.is_lowercase())
+                || (c.is_ascii_digit() && p.is_alphabetic())
+                || (c.is_uppercase() && p.is_ascii_digit())
+                || (c.is_uppercase() && caps >= 2 && next_lower)
+                || (c.is_lowercase() && caps == 2)
+        });
+        if split {
+            name.push(' ');
+        }
+        name.push(c);
+        caps = if c.is_uppercase() { caps + 1 } else { 0 };
+    }
+    name
 }
 
-fn load_data() -> Vec<Character> {
-    const JSON: &str = "streaks.json";
+/// Load both category files from `dir` as `(killer, survivor)` lists.
+fn load_all_categories(dir: &Path) -> (Vec<String>, Vec<String>) {
+    (
+        load_categories_from_file(
+            &dir.join(KILLER_STREAKS_FILE),
+            DEFAULT_KILLER_STREAK_CATEGORIES,
+        ),
+        load_categories_from_file(
+            &dir.join(SURVIVOR_STREAKS_FILE),
+            DEFAULT_SURVIVOR_STREAK_CATEGORIES,
+        ),
+    )
+}
+
+/// The characters saved in `dir`, repaired, migrated and with new portraits added,
+/// written back if any of that changed them. A save from a newer app is an error and
+/// is left as it is.
+fn load_data(dir: &Path, base: &Path) -> Result<Vec<Character>, schema::NewerVersion> {
     let mut data_changed = false;
 
-    let killer_cats =
-        load_categories_from_file("killer_streaks.txt", DEFAULT_KILLER_STREAK_CATEGORIES);
-    let survivor_cats =
-        load_categories_from_file("survivor_streaks.txt", DEFAULT_SURVIVOR_STREAK_CATEGORIES);
+    if let Err(e) = fs::create_dir_all(dir) {
+        log::warn!("Could not create {}: {}", dir.display(), e);
+    }
+    let (killer_cats, survivor_cats) = load_all_categories(dir);
 
-    let mut characters: Vec<Character> = if let Ok(file) = OpenOptions::new().read(true).open(JSON)
-    {
-        let reader = BufReader::new(&file);
-        serde_json::from_reader(reader).unwrap_or_default()
+    let json = dir.join(STREAKS_FILE);
+    let mut characters: Vec<Character> = match fs::read_to_string(&json) {
+        Ok(text) => match schema::parse(&text) {
+            Ok((characters, migrated)) => {
+                data_changed |= migrated;
+                characters
+            }
+            Err(e) => match e.downcast::<schema::NewerVersion>() {
+                Ok(newer) => {
+                    log::error!("Not loading {}: {}", json.display(), newer);
+                    return Err(*newer);
+                }
+                Err(e) => {
+                    log::warn!("Could not read {}: {}", json.display(), e);
+                    quarantine_save_file(&json);
+                    Vec::new()
+                }
+            },
+        },
+        Err(_) => Vec::new(),
+    };
+
+    if repair_streaks(&mut characters) {
+        data_changed = true;
+    }
+
+    if seed_survivor_totals(&mut characters) {
+        data_changed = true;
+    }
+
+    let media = base.join(MEDIA_DIR);
+    if !media.exists() {
+        match fs::create_dir_all(&media) {
+            Ok(()) => log::info!(
+                "Created {}; add a PNG portrait per character there and restart",
+                media.display()
+            ),
+            Err(e) => log::warn!("Could not create {}: {}", media.display(), e),
+        }
+    }
+
+    if scan_media(base, &mut characters, &killer_cats, &survivor_cats) {
+        data_changed = true;
+    }
+
+    // Without portraits there is nothing to pick, so start with a survivor entry.
+    if characters.is_empty() {
+        log::info!(
+            "No characters yet; add portraits to {} to track killers",
+            media.display()
+        );
+        characters.push(Character {
+            name: "Survivor".into(),
+            image_path: String::new(),
+            image_crop: None,
+            role: Role::Survivor,
+            favorite: false,
+            hidden: false,
+            color: None,
+            survivor_totals: SurvivorTotals::default(),
+            streaks: survivor_cats
+                .iter()
+                .map(|n| StreakCategory::new(n.clone()))
+                .collect(),
+        });
+        data_changed = true;
+    }
+
+    if ensure_all_categories(&mut characters, &killer_cats, &survivor_cats) {
+        data_changed = true;
+    }
+    for line in describe_unlisted(&characters, &killer_cats, &survivor_cats) {
+        log::warn!("Not in the category files: {}", line);
+    }
+
+    if data_changed {
+        save_or_log(dir, &characters);
+    }
+
+    start_session(&mut characters);
+    sort_characters(&mut characters);
+    Ok(characters)
+}
+
+/// Start old survivors' totals from their categories' results; true if any were.
+fn seed_survivor_totals(characters: &mut [Character]) -> bool {
+    let mut seeded = false;
+    for character in characters.iter_mut() {
+        if character.role != Role::Survivor || !character.survivor_totals.is_empty() {
+            continue;
+        }
+        let (wins, losses) = character
+            .streaks
+            .iter()
+            .fold((0, 0), |(w, l), cat| (w + cat.wins, l + cat.losses));
+        if wins + losses > 0 {
+            character.survivor_totals = SurvivorTotals {
+                games: wins + losses,
+                escapes: wins,
+            };
+            seeded = true;
+        }
+    }
+    seeded
+}
+
+/// Fix values a hand edit can leave impossible: negative counts become zero and a
+/// best below the current streak is raised to it. Returns whether anything changed.
+fn repair_streaks(characters: &mut [Character]) -> bool {
+    let mut repaired = false;
+    for character in characters.iter_mut() {
+        for cat in &mut character.streaks {
+            let before = cat.clone();
+            for value in [
+                &mut cat.current,
+                &mut cat.best,
+                &mut cat.wins,
+                &mut cat.losses,
+                &mut cat.worst,
+                &mut cat.loss_run,
+            ] {
+                *value = (*value).max(0);
+            }
+            cat.best = cat.best.max(cat.current);
+            cat.worst = cat.worst.max(cat.loss_run);
+            if cat.goal.is_some_and(|goal| goal <= 0) {
+                cat.goal = None;
+            }
+            if *cat != before {
+                log::warn!(
+                    "Repaired {} {}: current {} -> {}, best {} -> {}",
+                    character.name,
+                    cat.name,
+                    before.current,
+                    cat.current,
+                    before.best,
+                    cat.best
+                );
+                repaired = true;
+            }
+        }
+    }
+    repaired
+}
+
+/// Restart the session counters of every category.
+fn start_session(characters: &mut [Character]) {
+    for cat in characters.iter_mut().flat_map(|c| c.streaks.iter_mut()) {
+        cat.start_session();
+    }
+}
+
+/// Move an unreadable save out of the way so starting fresh doesn't overwrite it.
+fn quarantine_save_file(path: &Path) {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let aside = path.with_file_name(format!("streaks.corrupt-{}.json", stamp));
+    match fs::rename(path, &aside) {
+        Ok(()) => log::warn!(
+            "Moved unreadable save to {}; starting with fresh streaks",
+            aside.display()
+        ),
+        Err(e) => log::warn!("Could not move {} aside: {}", path.display(), e),
+    }
+}
+
+/// `media` and every folder below it, relative to `base`, in name order. Symlinked
+/// folders are skipped so a link back up the tree can't make the scan loop forever.
+fn media_dirs(base: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::from(MEDIA_DIR)];
+    let mut next = 0;
+    while let Some(dir) = dirs.get(next).cloned() {
+        next += 1;
+        let Ok(entries) = fs::read_dir(base.join(&dir)) else {
+            continue;
+        };
+        let mut children: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .map(|e| dir.join(e.file_name()))
+            .collect();
+        children.sort();
+        dirs.extend(children);
+    }
+    dirs
+}
+
+/// The role implied by a portrait's folder: `survivors` or `killers`, in any case.
+fn folder_role(dir: &Path) -> Option<Role> {
+    let name = dir.file_name()?.to_str()?;
+    if name.eq_ignore_ascii_case(SURVIVORS_DIR) {
+        Some(Role::Survivor)
+    } else if name.eq_ignore_ascii_case(KILLERS_DIR) {
+        Some(Role::Killer)
     } else {
-        Vec::new()
+        None
+    }
+}
+
+/// Whether `path` looks like a portrait the app can show.
+fn is_portrait(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| {
+            ["png", "jpg", "jpeg"]
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+}
+
+/// Copy an image from anywhere into `base/media`, ready for [`scan_media`] to pick up.
+/// Returns the name of the character it will become, or a message saying why it was
+/// refused.
+fn import_portrait(base: &Path, file: &Path, characters: &[Character]) -> Result<String, String> {
+    let (Some(file_name), Some(stem)) =
+        (file.file_name(), file.file_stem().and_then(|s| s.to_str()))
+    else {
+        return Err(format!("{} is not a file", file.display()));
     };
+    if !is_portrait(file) || !file.is_file() {
+        return Err(format!("{} is not a PNG or JPG image", file.display()));
+    }
+    let name = aliases::load(base)
+        .get(stem)
+        .cloned()
+        .unwrap_or_else(|| format_name(stem));
+    let target = base.join(MEDIA_DIR).join(file_name);
+    if characters.iter().any(|c| c.name == name) || target.exists() {
+        return Err(format!("{} is already in the list", name));
+    }
+    fs::create_dir_all(target.parent().unwrap_or(base))
+        .and_then(|_| fs::copy(file, &target))
+        .map_err(|e| format!("Could not copy {}: {}", file.display(), e))?;
+    log::info!("Copied {} to {}", file.display(), target.display());
+    Ok(name)
+}
 
-    if let Ok(entries) = fs::read_dir("media") {
+/// Add a character for every portrait under `base/media` that isn't tracked yet,
+/// named from `aliases.txt` or else the file name. Portraits directly in a
+/// `survivors` or `killers` folder get that role; elsewhere the role is guessed from
+/// the name. Image paths are stored relative to `base`. Returns whether any were added.
+fn scan_media(
+    base: &Path,
+    characters: &mut Vec<Character>,
+    killer_cats: &[String],
+    survivor_cats: &[String],
+) -> bool {
+    let aliases = aliases::load(base);
+    let mut added = false;
+    for dir in media_dirs(base) {
+        let role = folder_role(&dir);
+        let Ok(entries) = fs::read_dir(base.join(&dir)) else {
+            continue;
+        };
         for entry in entries.filter_map(Result::ok) {
-            let path = entry.path();
-            if path
-                .extension()
-                .and_then(|e| e.to_str())
-                .is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
-            {
+            let path = dir.join(entry.file_name());
+            if is_portrait(&path) {
                 if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
-                    let name = format_name(stem);
-                    if !characters.iter().any(|c| c.name == name) {
-                        let cats_to_use = if name.eq_ignore_ascii_case("survivor") {
-                            &survivor_cats
-                        } else {
-                            &killer_cats
-                        };
+                    let name = aliases
+                        .get(stem)
+                        .cloned()
+                        .unwrap_or_else(|| format_name(stem));
+                    let image_path: String = path.to_string_lossy().into();
+                    // A seeded character gets its portrait once one is added. Otherwise
+                    // match on the image too, so a renamed character keeps its portrait.
+                    if let Some(c) = characters
+                        .iter_mut()
+                        .find(|c| c.name == name && c.image_path.is_empty())
+                    {
+                        c.image_path = image_path;
+                        added = true;
+                    } else if !characters
+                        .iter()
+                        .any(|c| c.name == name || c.image_path == image_path)
+                    {
+                        let role = role.unwrap_or_else(|| Role::from_name(&name));
                         characters.push(Character {
                             name,
-                            image_path: path.to_string_lossy().into(),
-                            streaks: cats_to_use
+                            image_path,
+                            image_crop: None,
+                            role,
+                            favorite: false,
+                            hidden: false,
+                            color: None,
+                            survivor_totals: SurvivorTotals::default(),
+                            streaks: role
+                                .categories(killer_cats, survivor_cats)
                                 .iter()
-                                .map(|n| StreakCategory {
-                                    name: n.clone(),
-                                    current: 0,
-                                    best: 0,
-                                })
+                                .map(|n| StreakCategory::new(n.clone()))
                                 .collect(),
                         });
-                        data_changed = true;
+                        added = true;
                     }
                 }
             }
         }
     }
+    added
+}
 
-    let mut categories_updated = false;
-    for character in &mut characters {
-        let cats_to_use = if character.name.eq_ignore_ascii_case("survivor") {
-            &survivor_cats
-        } else {
-            &killer_cats
-        };
-        if ensure_categories(character, cats_to_use) {
-            categories_updated = true;
-        }
+/// Move the window onto the primary monitor, or the first one, unless enough of its top
+/// edge is on some monitor to drag it by. Sizes are left alone.
+fn keep_on_screen(window: &winit::window::Window) {
+    // Wayland doesn't tell windows where they are, nor let them move.
+    let Ok(position) = window.outer_position() else {
+        return;
+    };
+    let width = window.outer_size().width as i32;
+    let on_monitor = |monitor: &winit::monitor::MonitorHandle| {
+        let (origin, size) = (monitor.position(), monitor.size());
+        let overlap =
+            (position.x + width).min(origin.x + size.width as i32) - position.x.max(origin.x);
+        overlap >= ON_SCREEN_MIN.min(width)
+            && position.y >= origin.y
+            && position.y < origin.y + size.height as i32 - ON_SCREEN_MIN
+    };
+    let monitors: Vec<_> = window.available_monitors().collect();
+    if monitors.is_empty() || monitors.iter().any(on_monitor) {
+        return;
     }
-    if categories_updated {
-        data_changed = true;
+    let Some(monitor) = window
+        .primary_monitor()
+        .or_else(|| monitors.first().cloned())
+    else {
+        return;
+    };
+    let (origin, size) = (monitor.position(), monitor.size());
+    let height = window.outer_size().height as i32;
+    let x = position.x.clamp(
+        origin.x,
+        (origin.x + size.width as i32 - width).max(origin.x),
+    );
+    let y = position.y.clamp(
+        origin.y,
+        (origin.y + size.height as i32 - height).max(origin.y),
+    );
+    log::info!("Moved the window back on screen, to {}, {}", x, y);
+    window.set_outer_position(winit::dpi::PhysicalPosition::new(x, y));
+}
+
+/// Point undo history at the characters' new positions after a re-sort.
+fn remap_characters(stack: &mut [UndoAction], remap: &[usize]) {
+    for change in stack.iter_mut().flatten() {
+        change.char_idx = remap[change.char_idx];
     }
+}
 
-    if data_changed {
-        save_data(&characters).ok();
+/// Sort characters by name, returning where each old index ended up.
+fn sort_characters(characters: &mut Vec<Character>) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..characters.len()).collect();
+    order.sort_by(|&a, &b| characters[a].name.cmp(&characters[b].name));
+    let mut remap = vec![0; order.len()];
+    for (new_idx, &old_idx) in order.iter().enumerate() {
+        remap[old_idx] = new_idx;
     }
+    let mut taken: Vec<Option<Character>> = characters.drain(..).map(Some).collect();
+    characters.extend(order.iter().filter_map(|&i| taken[i].take()));
+    remap
+}
 
-    characters.sort_by(|a, b| a.name.cmp(&b.name));
-    characters
+/// Write the characters to `streaks.json` via a temporary file so a crash mid-write
+/// can never leave a truncated save behind.
+fn save_data(dir: &Path, characters: &[Character]) -> Result<(), Box<dyn Error>> {
+    let json = dir.join(STREAKS_FILE);
+    let tmp = dir.join("streaks.json.tmp");
+
+    let write_tmp = || -> Result<(), Box<dyn Error>> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&tmp)?;
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, &schema::SaveFileRef::new(characters))?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        Ok(())
+    };
+
+    if let Err(e) = write_tmp() {
+        fs::remove_file(&tmp).ok();
+        return Err(e);
+    }
+    if let Err(e) = backup_save_file(&json, &dir.join(BACKUP_DIR)) {
+        log::warn!("Could not back up {}: {}", json.display(), e);
+    }
+    fs::rename(&tmp, &json)?;
+    Ok(())
 }
 
-fn save_data(characters: &[Character]) -> Result<(), Box<dyn Error>> {
-    let file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open("streaks.json")?;
-    let writer = BufWriter::new(file);
-    serde_json::to_writer_pretty(writer, characters)?;
+/// Save from a UI callback, which has nowhere to report a failure but the log.
+fn save_or_log(dir: &Path, characters: &[Character]) {
+    if let Err(e) = save_data(dir, characters) {
+        log::error!("Could not save {}: {}", dir.join(STREAKS_FILE).display(), e);
+    }
+}
+
+/// Copy the existing save into the backup folder before it gets replaced.
+fn backup_save_file(path: &Path, backup_dir: &Path) -> Result<(), Box<dyn Error>> {
+    if !path.exists() {
+        return Ok(());
+    }
+    fs::create_dir_all(backup_dir)?;
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    fs::copy(path, backup_dir.join(format!("streaks-{}.json", stamp)))?;
+    rotate_backups(backup_dir, BACKUP_LIMIT)
+}
+
+/// Delete the oldest backups so at most `keep` remain. The timestamped names sort
+/// chronologically, so filename order is age order.
+fn rotate_backups(dir: &Path, keep: usize) -> Result<(), Box<dyn Error>> {
+    let mut backups: Vec<_> = fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("streaks-") && n.ends_with(".json"))
+        })
+        .collect();
+    backups.sort();
+    let excess = backups.len().saturating_sub(keep);
+    for old in &backups[..excess] {
+        fs::remove_file(old)?;
+    }
     Ok(())
 }
 
+/// Lock shared state, carrying on with the data if another thread panicked with it.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
 fn update_streak_display(ui: &AppWindow, character: &Character, streak_idx: usize) {
+    let rows: Vec<StreakRow> = character
+        .streaks
+        .iter()
+        .map(|cat| StreakRow {
+            name: cat.name.clone().into(),
+            current: numbers::format(cat.current).into(),
+            best: numbers::format(cat.best).into(),
+        })
+        .collect();
+    ui.set_streak_rows(Rc::new(VecModel::from(rows)).into());
     let i = streak_idx.min(character.streaks.len().saturating_sub(1));
     if let Some(cat) = character.streaks.get(i) {
         ui.set_counter(cat.current);
+        ui.set_counter_text(numbers::format(cat.current).into());
         ui.set_pbValue(cat.best);
+        ui.set_pb_text(numbers::format(cat.best).into());
+        ui.set_worst(cat.worst);
+        ui.set_worst_text(numbers::format(cat.worst).into());
+        ui.set_win_rate(cat.win_rate());
+        ui.set_games(cat.wins + cat.losses);
+        ui.set_games_text(numbers::format(cat.wins + cat.losses).into());
+        let (session_wins, session_losses) = cat.session_delta();
+        ui.set_session_wins(session_wins);
+        ui.set_session_losses(session_losses);
+        ui.set_pb_date(cat.best_date.clone().unwrap_or_default().into());
+        ui.set_goal(cat.goal.unwrap_or(0));
+        ui.set_goal_text(numbers::format(cat.goal.unwrap_or(0)).into());
+        ui.set_note(cat.note.clone().into());
+        ui.set_goal_progress(cat.goal_progress());
+        ui.set_goal_reached(false);
+        ui.set_frozen(cat.frozen);
         ui.set_selected_streak_category_index(i as i32);
     }
+    let totals = character.survivor_totals;
+    ui.set_survivor_games(totals.games);
+    ui.set_survivor_games_text(numbers::format(totals.games).into());
+    ui.set_escape_rate(totals.escape_rate());
+    let category_color = character.streaks.get(i).and_then(|c| c.color.as_deref());
+    ui.set_character_color(character.color.clone().unwrap_or_default().into());
+    ui.set_category_color(category_color.unwrap_or_default().into());
+    let accent = category_color
+        .or(character.color.as_deref())
+        .and_then(parse_hex_color);
+    ui.set_has_accent(accent.is_some());
+    ui.set_accent(accent.unwrap_or_default());
 }
 
-fn update_ui(ui: &AppWindow, character: &Character) {
-    ui.set_killer_name(character.name.clone().into());
-    let img = Image::load_from_path(Path::new(&character.image_path)).unwrap_or_default();
-    ui.set_killer_image(img);
-    let names: Vec<_> = character
-        .streaks
-        .iter()
-        .map(|s| s.name.clone().into())
-        .collect();
-    ui.set_streak_category_names(Rc::new(VecModel::from(names)).into());
+/// A color written as `#rrggbb` or `#rgb`, with or without the `#`.
+fn parse_hex_color(text: &str) -> Option<slint::Color> {
+    let hex = text.trim().trim_start_matches('#');
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let hex: String = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 => hex.to_string(),
+        _ => return None,
+    };
+    let value = u32::from_str_radix(&hex, 16).ok()?;
+    Some(slint::Color::from_rgb_u8(
+        (value >> 16) as u8,
+        (value >> 8) as u8,
+        value as u8,
+    ))
+}
+
+/// Every category name across the roster, in first-seen order, with the highest best
+/// (and who holds it) and the sum of the current streaks.
+fn roster_totals(characters: &[Character]) -> Vec<TotalRow> {
+    // (name, current, best, holder)
+    let mut totals: Vec<(&str, i32, i32, &str)> = Vec::new();
+    for character in characters {
+        for cat in &character.streaks {
+            match totals.iter_mut().find(|t| t.0 == cat.name) {
+                Some(total) => {
+                    total.1 += cat.current;
+                    if cat.best > total.2 {
+                        total.2 = cat.best;
+                        total.3 = &character.name;
+                    }
+                }
+                None => totals.push((&cat.name, cat.current, cat.best, &character.name)),
+            }
+        }
+    }
+    totals
+        .into_iter()
+        .map(|(name, current, best, holder)| TotalRow {
+            name: name.into(),
+            current: numbers::format(current).into(),
+            best: numbers::format(best).into(),
+            holder: holder.into(),
+        })
+        .collect()
+}
+
+fn update_totals(ui: &AppWindow, characters: &[Character]) {
+    ui.set_total_rows(Rc::new(VecModel::from(roster_totals(characters))).into());
+}
+
+fn update_ui(ui: &AppWindow, portraits: &portraits::Portraits, character: &Character) {
+    ui.set_killer_name(character.name.clone().into());
+    ui.set_favorite(character.favorite);
+    ui.set_is_survivor(character.role == Role::Survivor);
+    ui.set_hidden(character.hidden);
+    ui.set_killer_image(portraits.get(&character.image_path));
+    let crop = character.image_crop.unwrap_or_default();
+    ui.set_crop_x(crop.x);
+    ui.set_crop_y(crop.y);
+    ui.set_crop_zoom(crop.zoom);
+    let names: Vec<_> = character
+        .streaks
+        .iter()
+        .map(|s| s.name.clone().into())
+        .collect();
+    ui.set_streak_category_names(Rc::new(VecModel::from(names)).into());
+}
+
+fn update_button_labels(ui: &AppWindow, buttons: &config::ButtonConfig) {
+    // A label left at its English default is sent empty, so the UI shows it translated.
+    let defaults = config::ButtonConfig::default();
+    let label = |text: &String, default: &String| -> slint::SharedString {
+        if text == default {
+            "".into()
+        } else {
+            text.clone().into()
+        }
+    };
+    ui.set_killer_win_label(label(&buttons.killer_win, &defaults.killer_win));
+    ui.set_killer_loss_label(label(&buttons.killer_loss, &defaults.killer_loss));
+    ui.set_survivor_win_label(label(&buttons.survivor_win, &defaults.survivor_win));
+    ui.set_survivor_loss_label(label(&buttons.survivor_loss, &defaults.survivor_loss));
+    ui.set_swap_result_buttons(buttons.swap);
+}
+
+/// Languages the UI is translated into, after the system default, as offered in
+/// Preferences. English is the built-in text.
+const LANGUAGES: [(&str, &str); 3] = [("", "System"), ("en", "English"), ("de", "Deutsch")];
+
+/// Show the UI in `language` (like `de`), or in the system's language when it's empty.
+/// Languages without a translation fall back to English.
+fn select_language(language: &str) {
+    let wanted = if language.is_empty() {
+        sys_locale::get_locale().unwrap_or_default()
+    } else {
+        language.to_string()
+    };
+    let base = wanted.split(['-', '_', '.']).next().unwrap_or_default();
+    if slint::select_bundled_translation(base).is_err() {
+        if !language.is_empty() {
+            log::warn!("No {} translation; using English", language);
+        }
+        let _ = slint::select_bundled_translation("en");
+    }
+}
+
+/// Tell the user why the app isn't starting; release builds have no console for the
+/// message on stderr. The config may not have been read, so this follows the system
+/// language.
+fn show_message(message: &str) {
+    select_language("");
+    let shown = MessageWindow::new().and_then(|window| {
+        window.set_message(message.into());
+        window.on_close({
+            let window = window.as_weak();
+            move || {
+                if let Some(Err(e)) = window.upgrade().map(|w| w.hide()) {
+                    eprintln!("Could not close the message: {}", e);
+                }
+            }
+        });
+        window.run()
+    });
+    if let Err(e) = shown {
+        eprintln!("Could not show the message: {}", e);
+    }
+}
+
+fn profiles_model(profiles: &[String]) -> Vec<slint::SharedString> {
+    profiles.iter().map(|p| p.clone().into()).collect()
+}
+
+/// Watch a profile directory for edits to its category and promotion files.
+fn watch_categories(dir: &Path) -> Option<watch::DebouncedWatcher> {
+    watch::DebouncedWatcher::new(&[dir], watch::RecursiveMode::NonRecursive)
+        .map_err(|e| log::warn!("Could not watch category files: {}", e))
+        .ok()
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let characters = Rc::new(RefCell::new(load_data()));
-    let current_char_idx = Rc::new(RefCell::new(0));
-    let current_streak_idx = Rc::new(RefCell::new(0));
+    let cli = cli::Cli::parse();
+    let dirs = paths::resolve(cli.data_dir);
+    for dir in [&dirs.data, &dirs.config] {
+        if let Err(e) = fs::create_dir_all(dir) {
+            eprintln!("Could not create {}: {}", dir.display(), e);
+        }
+    }
+    // Taken before anything is read or written, so a second copy can't migrate, repair
+    // or save the first one's files. Released when main returns, after the final save.
+    let Some(_instance) = instance::acquire(&dirs.data) else {
+        let message = format!(
+            "DBD Winstreak is already running for {}. Switch to that window, or close it first.",
+            dirs.data.display()
+        );
+        eprintln!("{}", message);
+        if cli.command.is_none() {
+            show_message(&message);
+        }
+        std::process::exit(1);
+    };
+    let base_dir = Rc::new(dirs.data.clone());
+    let config_dir = Rc::new(dirs.config.clone());
+    logging::init(&base_dir);
+    paths::migrate_legacy(&dirs);
+    let config = Rc::new(RefCell::new(config::load(&config_dir)));
+    // Saves, profiles and `media/` may each live elsewhere; logs, sounds and the
+    // instance lock stay in the base.
+    let data_root = Rc::new(config.borrow().data_root(&base_dir));
+    let media_root = Rc::new(config.borrow().media_root(&base_dir));
+    paths::seed_media(&media_root);
+    let data_dir = Rc::new(RefCell::new(profile::profile_dir(
+        &data_root,
+        &config.borrow().profile,
+    )));
+    // The characters and selection are shared with background threads such as the
+    // HTTP server; everything else only lives on the UI thread.
+    let loaded = match load_data(&data_dir.borrow(), &media_root) {
+        Ok(loaded) => loaded,
+        Err(newer) => {
+            let message = format!(
+                "{} was saved by a newer version of DBD Winstreak ({}). Update the app to use it; the file has been left as it is.",
+                data_dir.borrow().join(STREAKS_FILE).display(),
+                newer
+            );
+            eprintln!("{}", message);
+            if cli.command.is_none() {
+                show_message(&message);
+            }
+            std::process::exit(1);
+        }
+    };
+    let characters = Arc::new(Mutex::new(loaded));
+    if let Some(command) = cli.command {
+        if let Err(e) = cli::run(
+            command,
+            &data_dir.borrow(),
+            &config.borrow().counter,
+            &mut lock(&characters),
+        ) {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    let autosave = Rc::new(autosave::Autosave::start(
+        characters.clone(),
+        data_dir.borrow().clone(),
+        &config.borrow().autosave,
+    ));
+    log::info!(
+        "Loaded {} characters from {}",
+        lock(&characters).len(),
+        data_dir.borrow().display()
+    );
+    let (start_char, start_streak) = session::load(&data_dir.borrow()).resolve(&lock(&characters));
+    let current_char_idx = Arc::new(AtomicUsize::new(start_char));
+    let current_streak_idx = Arc::new(AtomicUsize::new(start_streak));
+    let undo_stack: Rc<RefCell<Vec<UndoAction>>> = Rc::new(RefCell::new(Vec::new()));
+    let redo_stack: Rc<RefCell<Vec<UndoAction>>> = Rc::new(RefCell::new(Vec::new()));
+    // The real characters while practice mode is on; everything recorded meanwhile is
+    // thrown away when it's turned off.
+    let practice: Rc<RefCell<Option<Vec<Character>>>> = Rc::new(RefCell::new(None));
+    let killer_list = Rc::new(RefCell::new(killer_list::KillerList::new(
+        config.borrow().killer_sort,
+    )));
+    let promotions = Rc::new(RefCell::new(promotions::load(&data_dir.borrow())));
+    let sounds = Rc::new(sound::Sounds::new());
+    let portraits = Rc::new(portraits::Portraits::new(
+        media_root.to_path_buf(),
+        &config.borrow().portraits,
+    ));
     let ui = AppWindow::new()?;
-
-    if let Some(c) = characters.borrow().first() {
-        update_ui(&ui, c);
-        update_streak_display(&ui, c, 0);
-        let names: Vec<_> = characters
-            .borrow()
+    // Applied when the window is created; see `keep_on_screen` for the monitor check.
+    if let Some(geometry) = config.borrow().window {
+        ui.window()
+            .set_position(slint::PhysicalPosition::new(geometry.x, geometry.y));
+        ui.window()
+            .set_size(slint::PhysicalSize::new(geometry.width, geometry.height));
+    }
+    select_language(&config.borrow().language);
+    ui.set_sort_mode_index(
+        killer_list::SortMode::ALL
             .iter()
-            .map(|c| c.name.clone().into())
-            .collect();
-        ui.set_killer_names(Rc::new(VecModel::from(names)).into());
-        ui.set_selected_killer_index(0);
+            .position(|&m| m == config.borrow().killer_sort)
+            .unwrap_or(0) as i32,
+    );
+    ui.set_dark_theme(config.borrow().theme == config::Theme::Dark);
+    let profiles = profile::list_profiles(&data_root);
+    ui.set_selected_profile_index(
+        profiles
+            .iter()
+            .position(|p| *p == config.borrow().profile)
+            .unwrap_or(0) as i32,
+    );
+    ui.set_profile_names(Rc::new(VecModel::from(profiles_model(&profiles))).into());
+
+    {
+        let list = lock(&characters);
+        if let Some(c) = list.get(start_char) {
+            update_ui(&ui, &portraits, c);
+            update_streak_display(&ui, c, start_streak);
+            let mut killers = killer_list.borrow_mut();
+            killers.rebuild(&list);
+            update_totals(&ui, &list);
+            killers.show(&ui, &list, start_char);
+        }
     }
 
+    // Shows the selected streak outside the window: the OBS overlay files, the
+    // Discord status and the HTTP server's `/current`.
+    let presence = Rc::new(discord::Presence::default());
+    let http_current = http::Publisher::default();
+    let publish_streak = {
+        let config = config.clone();
+        let base_dir = base_dir.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        let presence = presence.clone();
+        let http_current = http_current.clone();
+        move || {
+            let config = config.borrow();
+            let list = lock(&characters);
+            let character = list.get(current_char_idx.load(Ordering::Relaxed));
+            let streak_idx = current_streak_idx.load(Ordering::Relaxed);
+            if let Some(character) = character.filter(|_| config.overlay.enabled) {
+                if let Err(e) = overlay::write(&config.overlay, &base_dir, character, streak_idx) {
+                    log::warn!("Could not write {}: {}", config.overlay.file, e);
+                }
+            }
+            presence.update(&config.discord, character, streak_idx);
+            if config.http.enabled {
+                http_current.publish(character, streak_idx);
+            }
+        }
+    };
+    publish_streak();
+
+    // Remembers the selection so the next launch starts where this one left off.
+    let save_session = {
+        let data_dir = data_dir.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        move || {
+            let list = lock(&characters);
+            let session = session::Session::capture(
+                &list,
+                current_char_idx.load(Ordering::Relaxed),
+                current_streak_idx.load(Ordering::Relaxed),
+            );
+            if let Err(e) = session::save(&data_dir.borrow(), &session) {
+                log::warn!("Could not save {}: {}", session::SESSION_FILE, e);
+            }
+        }
+    };
+
     ui.on_killer_selected({
+        let publish_streak = publish_streak.clone();
+        let save_session = save_session.clone();
         let ui_weak = ui.as_weak();
+        let portraits = portraits.clone();
         let characters = characters.clone();
         let current_char_idx = current_char_idx.clone();
         let current_streak_idx = current_streak_idx.clone();
+        let killer_list = killer_list.clone();
         move |name| {
             if let Some(ui) = ui_weak.upgrade() {
-                if let Some(idx) = characters
-                    .borrow()
-                    .iter()
-                    .position(|c| c.name == name.as_str())
-                {
-                    *current_char_idx.borrow_mut() = idx;
-                    *current_streak_idx.borrow_mut() = 0;
-                    if let Some(character) = characters.borrow().get(idx) {
-                        update_ui(&ui, character);
-                        update_streak_display(&ui, character, 0);
-                        ui.set_selected_killer_index(idx as i32);
+                let list = lock(&characters);
+                let idx = killer_list.borrow().index_of(&name);
+                if let Some(idx) = idx.filter(|&i| i < list.len()) {
+                    current_char_idx.store(idx, Ordering::Relaxed);
+                    current_streak_idx.store(0, Ordering::Relaxed);
+                    update_ui(&ui, &portraits, &list[idx]);
+                    update_streak_display(&ui, &list[idx], 0);
+                    killer_list.borrow().select(&ui, idx);
+                    drop(list);
+                    save_session();
+                    publish_streak();
+                }
+            }
+        }
+    });
+
+    // Copies the selected streak, formatted like the OBS text file. The clipboard is
+    // kept open because on Linux its contents vanish when it's dropped.
+    ui.on_copy_streak({
+        let config = config.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        let clipboard: RefCell<Option<arboard::Clipboard>> = RefCell::new(None);
+        move || {
+            let text = match lock(&characters).get(current_char_idx.load(Ordering::Relaxed)) {
+                Some(character) => overlay::render(
+                    &config.borrow().overlay.template,
+                    character,
+                    current_streak_idx.load(Ordering::Relaxed),
+                ),
+                None => return,
+            };
+            let mut clipboard = clipboard.borrow_mut();
+            if clipboard.is_none() {
+                match arboard::Clipboard::new() {
+                    Ok(c) => *clipboard = Some(c),
+                    Err(e) => {
+                        log::warn!("Clipboard unavailable: {}", e);
+                        return;
                     }
                 }
             }
+            if let Some(Err(e)) = clipboard.as_mut().map(|c| c.set_text(text)) {
+                log::warn!("Could not copy to the clipboard: {}", e);
+            }
+        }
+    });
+
+    // Steps through the dropdown in its displayed order, wrapping around at the ends.
+    let step_killer = {
+        let ui_weak = ui.as_weak();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let killer_list = killer_list.clone();
+        move |forward: bool| {
+            let Some(ui) = ui_weak.upgrade() else {
+                return;
+            };
+            let target = killer_list
+                .borrow()
+                .step(current_char_idx.load(Ordering::Relaxed), forward);
+            let name = target.and_then(|i| lock(&characters).get(i).map(|c| c.name.clone()));
+            if let Some(name) = name {
+                ui.invoke_killer_selected(name.into());
+            }
+        }
+    };
+
+    ui.on_previous_killer({
+        let step_killer = step_killer.clone();
+        move || step_killer(false)
+    });
+
+    ui.on_next_killer({
+        let step_killer = step_killer.clone();
+        move || step_killer(true)
+    });
+
+    // Re-applies the killer list's filter and sort, e.g. after streak values change.
+    let refresh_killer_list = {
+        let ui_weak = ui.as_weak();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let killer_list = killer_list.clone();
+        move || {
+            if let Some(ui) = ui_weak.upgrade() {
+                let list = lock(&characters);
+                let mut killers = killer_list.borrow_mut();
+                killers.rebuild(&list);
+                update_totals(&ui, &list);
+                killers.show(&ui, &list, current_char_idx.load(Ordering::Relaxed));
+            }
+        }
+    };
+
+    // Practice works on a copy that's thrown away, so character edits made meanwhile
+    // would be lost. They're refused instead, putting the window back as it was.
+    let refuse_in_practice = {
+        let ui_weak = ui.as_weak();
+        let portraits = portraits.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let practice = practice.clone();
+        move || {
+            if practice.borrow().is_none() {
+                return false;
+            }
+            if let Some(ui) = ui_weak.upgrade() {
+                if let Some(character) =
+                    lock(&characters).get(current_char_idx.load(Ordering::Relaxed))
+                {
+                    update_ui(&ui, &portraits, character);
+                }
+                ui.set_character_status("Leave practice mode to edit characters".into());
+            }
+            true
+        }
+    };
+
+    ui.on_crop_changed({
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let refuse_in_practice = refuse_in_practice.clone();
+        move |x, y, zoom| {
+            if refuse_in_practice() {
+                return;
+            }
+            let crop = ImageCrop {
+                x: x.clamp(0.0, 1.0),
+                y: y.clamp(0.0, 1.0),
+                zoom: zoom.max(1.0),
+            };
+            if let Some(character) =
+                lock(&characters).get_mut(current_char_idx.load(Ordering::Relaxed))
+            {
+                character.image_crop = (crop != ImageCrop::default()).then_some(crop);
+            }
+            if let Some(ui) = ui_weak.upgrade() {
+                ui.set_crop_x(crop.x);
+                ui.set_crop_y(crop.y);
+                ui.set_crop_zoom(crop.zoom);
+            }
+            autosave.mark_dirty();
+        }
+    });
+
+    ui.on_favorite_toggled({
+        let autosave = autosave.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let refresh_killer_list = refresh_killer_list.clone();
+        let refuse_in_practice = refuse_in_practice.clone();
+        move |favorite| {
+            if refuse_in_practice() {
+                return;
+            }
+            if let Some(character) =
+                lock(&characters).get_mut(current_char_idx.load(Ordering::Relaxed))
+            {
+                character.favorite = favorite;
+            }
+            autosave.mark_dirty();
+            refresh_killer_list();
+        }
+    });
+
+    // Hiding only changes the dropdown; the character's streaks are kept as they are.
+    ui.on_hidden_toggled({
+        let autosave = autosave.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let refresh_killer_list = refresh_killer_list.clone();
+        let refuse_in_practice = refuse_in_practice.clone();
+        move |hidden| {
+            if refuse_in_practice() {
+                return;
+            }
+            if let Some(character) =
+                lock(&characters).get_mut(current_char_idx.load(Ordering::Relaxed))
+            {
+                character.hidden = hidden;
+            }
+            autosave.mark_dirty();
+            refresh_killer_list();
+        }
+    });
+
+    ui.on_show_hidden_toggled({
+        let killer_list = killer_list.clone();
+        let refresh_killer_list = refresh_killer_list.clone();
+        move |show| {
+            killer_list.borrow_mut().show_hidden = show;
+            refresh_killer_list();
+        }
+    });
+
+    ui.on_killer_filter_changed({
+        let killer_list = killer_list.clone();
+        let refresh_killer_list = refresh_killer_list.clone();
+        move |filter| {
+            killer_list.borrow_mut().filter = filter.to_string();
+            refresh_killer_list();
+        }
+    });
+
+    ui.on_sort_mode_selected({
+        let config = config.clone();
+        let config_dir = config_dir.clone();
+        let killer_list = killer_list.clone();
+        let refresh_killer_list = refresh_killer_list.clone();
+        move |index| {
+            let Some(&mode) = killer_list::SortMode::ALL.get(index as usize) else {
+                return;
+            };
+            killer_list.borrow_mut().sort = mode;
+            refresh_killer_list();
+            let mut config = config.borrow_mut();
+            config.killer_sort = mode;
+            if let Err(e) = config::save(&config_dir, &config) {
+                log::warn!("Could not save {}: {}", config::CONFIG_FILE, e);
+            }
+        }
+    });
+
+    ui.set_overlay_enabled(config.borrow().overlay.enabled);
+    ui.on_overlay_toggled({
+        let config = config.clone();
+        let config_dir = config_dir.clone();
+        let publish_streak = publish_streak.clone();
+        move |enabled| {
+            {
+                let mut config = config.borrow_mut();
+                config.overlay.enabled = enabled;
+                if let Err(e) = config::save(&config_dir, &config) {
+                    log::warn!("Could not save {}: {}", config::CONFIG_FILE, e);
+                }
+            }
+            publish_streak();
+        }
+    });
+
+    ui.set_sound_enabled(config.borrow().sound.enabled);
+    ui.on_sound_toggled({
+        let config = config.clone();
+        let config_dir = config_dir.clone();
+        move |enabled| {
+            let mut config = config.borrow_mut();
+            config.sound.enabled = enabled;
+            if let Err(e) = config::save(&config_dir, &config) {
+                log::warn!("Could not save {}: {}", config::CONFIG_FILE, e);
+            }
+        }
+    });
+
+    update_button_labels(&ui, &config.borrow().buttons);
+    ui.set_confirm_big_losses(config.borrow().confirm_loss.enabled);
+    ui.on_confirm_big_losses_toggled({
+        let config = config.clone();
+        let config_dir = config_dir.clone();
+        move |enabled| {
+            let mut config = config.borrow_mut();
+            config.confirm_loss.enabled = enabled;
+            if let Err(e) = config::save(&config_dir, &config) {
+                log::warn!("Could not save {}: {}", config::CONFIG_FILE, e);
+            }
+        }
+    });
+
+    ui.set_keep_on_top(config.borrow().always_on_top);
+    // Rewritten on every launch so the entry follows the executable if it was moved.
+    if config.borrow().start_with_windows {
+        if let Err(e) = startup::set_enabled(true) {
+            log::warn!("{}", e);
+        }
+    }
+    ui.on_always_on_top_toggled({
+        let config = config.clone();
+        let config_dir = config_dir.clone();
+        move |enabled| {
+            let mut config = config.borrow_mut();
+            config.always_on_top = enabled;
+            if let Err(e) = config::save(&config_dir, &config) {
+                log::warn!("Could not save {}: {}", config::CONFIG_FILE, e);
+            }
+        }
+    });
+
+    ui.on_theme_changed({
+        let config = config.clone();
+        let config_dir = config_dir.clone();
+        move |dark| {
+            let mut config = config.borrow_mut();
+            config.theme = if dark {
+                config::Theme::Dark
+            } else {
+                config::Theme::Light
+            };
+            if let Err(e) = config::save(&config_dir, &config) {
+                log::warn!("Could not save {}: {}", config::CONFIG_FILE, e);
+            }
         }
     });
 
     ui.on_streak_category_selected({
+        let publish_streak = publish_streak.clone();
+        let save_session = save_session.clone();
         let ui_weak = ui.as_weak();
         let characters = characters.clone();
         let current_char_idx = current_char_idx.clone();
         let current_streak_idx = current_streak_idx.clone();
         move |cat| {
             if let Some(ui) = ui_weak.upgrade() {
-                let char_idx = *current_char_idx.borrow();
-                if let Some(character) = characters.borrow().get(char_idx) {
+                let char_idx = current_char_idx.load(Ordering::Relaxed);
+                if let Some(character) = lock(&characters).get(char_idx) {
                     if let Some(pos) = character
                         .streaks
                         .iter()
                         .position(|s| s.name == cat.as_str())
                     {
-                        *current_streak_idx.borrow_mut() = pos;
+                        current_streak_idx.store(pos, Ordering::Relaxed);
                         update_streak_display(&ui, character, pos);
                     }
                 }
+                save_session();
+                publish_streak();
+            }
+        }
+    });
+
+    // Pushes a fresh action onto the undo stack, invalidating anything that was undone.
+    let push_undo = {
+        let ui_weak = ui.as_weak();
+        let undo_stack = undo_stack.clone();
+        let redo_stack = redo_stack.clone();
+        move |action: UndoAction| {
+            if action.is_empty() {
+                return;
+            }
+            let mut stack = undo_stack.borrow_mut();
+            stack.push(action);
+            if stack.len() > UNDO_LIMIT {
+                stack.remove(0);
+            }
+            redo_stack.borrow_mut().clear();
+            if let Some(ui) = ui_weak.upgrade() {
+                ui.set_can_undo(true);
+                ui.set_can_redo(false);
+            }
+        }
+    };
+
+    // Rebuilds whichever of the history views (summary, maps) are open.
+    let refresh_summary = {
+        let data_dir = data_dir.clone();
+        let ui_weak = ui.as_weak();
+        move || {
+            let Some(ui) = ui_weak.upgrade() else {
+                return;
+            };
+            if !ui.get_show_summary() && !ui.get_show_maps() {
+                return;
+            }
+            let entries = history::load(&data_dir.borrow());
+            if ui.get_show_summary() {
+                let period = if ui.get_summary_monthly() {
+                    summary::Period::Month
+                } else {
+                    summary::Period::Week
+                };
+                let rows = summary::summarize(&entries, period);
+                ui.set_summary_rows(Rc::new(VecModel::from(rows)).into());
+            }
+            if ui.get_show_maps() {
+                let sort = map_stats::MapSort::ALL
+                    .get(ui.get_map_sort_index() as usize)
+                    .copied()
+                    .unwrap_or(map_stats::MapSort::Killer);
+                let rows = map_stats::by_map(&entries, sort);
+                ui.set_map_rows(Rc::new(VecModel::from(rows)).into());
             }
         }
+    };
+
+    ui.on_summary_requested({
+        let refresh_summary = refresh_summary.clone();
+        move || refresh_summary()
     });
 
     let record = {
+        let refresh_summary = refresh_summary.clone();
+        let publish_streak = publish_streak.clone();
+        let data_dir = data_dir.clone();
+        let autosave = autosave.clone();
         let ui_weak = ui.as_weak();
         let characters_ref = characters.clone();
         let current_char_idx_ref = current_char_idx.clone();
         let current_streak_idx_ref = current_streak_idx.clone();
-        move |is_win: bool| {
-            if let Ok(mut list) = characters_ref.try_borrow_mut() {
-                let char_idx = *current_char_idx_ref.borrow();
-                let s_idx = *current_streak_idx_ref.borrow();
-                if let Some(character) = list.get_mut(char_idx) {
-                    if let Some(cat) = character.streaks.get_mut(s_idx) {
-                        if is_win {
-                            cat.current += 1;
-                            cat.best = cat.best.max(cat.current);
-                        } else {
-                            cat.current = 0;
-                        }
-                    }
-
-                    // This killer-specific logic should not run for survivor.
-                    if is_win && !character.name.eq_ignore_ascii_case("survivor") {
-                        if let Some(best_4k) = character
-                            .streaks
-                            .iter()
-                            .find(|s| s.name == "4k")
-                            .map(|s| s.best)
-                        {
-                            if let Some(three_k_streak) =
-                                character.streaks.iter_mut().find(|s| s.name == "3k")
-                            {
-                                three_k_streak.best = three_k_streak.best.max(best_4k);
-                            }
-                        }
+        let push_undo = push_undo.clone();
+        let refresh_killer_list = refresh_killer_list.clone();
+        let config = config.clone();
+        let base_dir = base_dir.clone();
+        let promotions = promotions.clone();
+        let sounds = sounds.clone();
+        let practice = practice.clone();
+        let toast_timer = Rc::new(slint::Timer::default());
+        // Records `count` results in a row as one undo step, each logged to the history
+        // and promoted on its own.
+        move |is_win: bool, count: i32| {
+            let (map, build) = ui_weak
+                .upgrade()
+                .map(|ui| {
+                    (
+                        ui.get_match_map().trim().to_string(),
+                        ui.get_match_build().trim().to_string(),
+                    )
+                })
+                .unwrap_or_default();
+            let mut list = lock(&characters_ref);
+            let char_idx = current_char_idx_ref.load(Ordering::Relaxed);
+            let s_idx = current_streak_idx_ref.load(Ordering::Relaxed);
+            let mut action = UndoAction::new();
+            let mut new_pb = false;
+            // Celebrate only on a win that lands exactly on the goal.
+            let mut reached = false;
+            // The last milestone a win landed on, if any.
+            let mut milestone = None;
+            for _ in 0..count {
+                let (step, step_pb) = record_result(
+                    &mut list,
+                    &promotions.borrow(),
+                    &config.borrow().counter,
+                    char_idx,
+                    s_idx,
+                    is_win,
+                );
+                // A frozen category took nothing, so there is nothing to log either.
+                if step.is_empty() {
+                    break;
+                }
+                // Undo restores the state from before the first step.
+                for change in step {
+                    if !action
+                        .iter()
+                        .any(|c| c.char_idx == change.char_idx && c.streak_idx == change.streak_idx)
+                    {
+                        action.push(change);
                     }
-
-                    if let Some(ui) = ui_weak.upgrade() {
-                        update_streak_display(&ui, &character, s_idx);
+                }
+                new_pb |= step_pb;
+                let Some(character) = list.get(char_idx) else {
+                    break;
+                };
+                if let Some(cat) = character.streaks.get(s_idx).filter(|_| is_win) {
+                    reached |= cat.goal == Some(cat.current);
+                    if config.borrow().milestones.reached(cat.current) {
+                        milestone = Some(cat.current);
                     }
                 }
+                if practice.borrow().is_none() {
+                    log_result(&data_dir.borrow(), character, s_idx, is_win, &map, &build);
+                }
+            }
+            if action.is_empty() {
+                log::info!("The selected category is frozen; the result was not recorded");
+                return;
+            }
+            if let (Some(character), Some(ui)) = (list.get(char_idx), ui_weak.upgrade()) {
+                update_streak_display(&ui, character, s_idx);
+                ui.set_goal_reached(reached);
+                // The next match is likely on another map, while the build tends to stay.
+                ui.set_match_map("".into());
+                if let Some(streak) = milestone {
+                    ui.set_milestone(streak);
+                    let ui_weak = ui_weak.clone();
+                    toast_timer.start(slint::TimerMode::SingleShot, MILESTONE_TOAST, move || {
+                        if let Some(ui) = ui_weak.upgrade() {
+                            ui.set_milestone(0);
+                        }
+                    });
+                }
+            }
 
-                drop(list);
+            drop(list);
+
+            push_undo(action);
+            autosave.mark_dirty();
+            refresh_killer_list();
+            refresh_summary();
+            publish_streak();
+
+            let config = config.borrow();
+            let sound = &config.sound;
+            if sound.enabled {
+                if new_pb || (milestone.is_some() && config.milestones.sound) {
+                    sounds.play(&base_dir.join(&sound.pb));
+                } else if !is_win {
+                    sounds.play(&base_dir.join(&sound.loss));
+                }
+            }
+        }
+    };
 
-                save_data(&characters_ref.borrow()).ok();
+    // One result from a button, hotkey or the tray. A second one straight after is
+    // dropped, since a bouncing key or a double click would otherwise count twice.
+    let record_one = {
+        let record = record.clone();
+        let config = config.clone();
+        let last = Rc::new(Cell::new(None::<Instant>));
+        move |is_win: bool| {
+            let cooldown = Duration::from_millis(config.borrow().counter.cooldown_ms);
+            let now = Instant::now();
+            if last
+                .get()
+                .is_some_and(|at| now.duration_since(at) < cooldown)
+            {
+                log::debug!(
+                    "Ignored a result recorded within {:?} of the last",
+                    cooldown
+                );
+                return;
             }
+            last.set(Some(now));
+            record(is_win, 1)
         }
     };
 
     ui.on_record_win({
-        let r = record.clone();
+        let r = record_one.clone();
         move || r(true)
     });
 
-    ui.on_record_loss({
+    ui.on_record_wins({
         let r = record.clone();
+        move |count| {
+            if (1..=MAX_BATCH_WINS).contains(&count) {
+                r(true, count)
+            } else {
+                log::warn!("Can only record 1 to {} wins at once", MAX_BATCH_WINS);
+            }
+        }
+    });
+
+    ui.on_record_loss({
+        let r = record_one.clone();
         move || r(false)
     });
 
-    ui.run()?;
-    Ok(())
+    // Every loss asks first if it would end a streak above the threshold. The global
+    // hotkey and the tray can fire while the window is hidden, so it's brought up to
+    // ask there too.
+    let request_loss = {
+        let r = record_one.clone();
+        let config = config.clone();
+        let ui_weak = ui.as_weak();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        move || {
+            let current = lock(&characters)
+                .get(current_char_idx.load(Ordering::Relaxed))
+                .and_then(|c| c.streaks.get(current_streak_idx.load(Ordering::Relaxed)))
+                .filter(|cat| !cat.frozen)
+                .map_or(0, |cat| cat.current);
+            if !config.borrow().confirm_loss.needed(current) {
+                r(false);
+                return;
+            }
+            let Some(ui) = ui_weak.upgrade() else {
+                return;
+            };
+            if !ui.window().is_visible() || ui.window().is_minimized() {
+                log::info!(
+                    "Showing the window to confirm losing a streak of {}",
+                    current
+                );
+                ui.window().set_minimized(false);
+                if let Err(e) = ui.show() {
+                    log::warn!("Could not show the window: {}", e);
+                }
+            }
+            ui.set_confirming_loss(true);
+        }
+    };
+    ui.on_request_loss({
+        let request_loss = request_loss.clone();
+        move || request_loss()
+    });
+
+    ui.on_practice_toggled({
+        let refresh_summary = refresh_summary.clone();
+        let publish_streak = publish_streak.clone();
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        let undo_stack = undo_stack.clone();
+        let redo_stack = redo_stack.clone();
+        let refresh_killer_list = refresh_killer_list.clone();
+        let portraits = portraits.clone();
+        let practice = practice.clone();
+        move |enabled| {
+            if enabled == practice.borrow().is_some() {
+                return;
+            }
+            if enabled {
+                autosave.pause();
+                *practice.borrow_mut() = Some(lock(&characters).clone());
+            } else {
+                let Some(real) = practice.borrow_mut().take() else {
+                    return;
+                };
+                let mut list = lock(&characters);
+                // The real list may be in another order, so keep the same character.
+                let name = list
+                    .get(current_char_idx.load(Ordering::Relaxed))
+                    .map(|c| c.name.clone());
+                *list = real;
+                let selected = name
+                    .and_then(|name| list.iter().position(|c| c.name == name))
+                    .unwrap_or(0);
+                current_char_idx.store(selected, Ordering::Relaxed);
+                autosave.resume();
+                // Undo entries from practice would bring the scratch values back.
+                undo_stack.borrow_mut().clear();
+                redo_stack.borrow_mut().clear();
+                if let Some(ui) = ui_weak.upgrade() {
+                    ui.set_can_undo(false);
+                    ui.set_can_redo(false);
+                    ui.set_character_status("".into());
+                    if let Some(character) = list.get(selected) {
+                        update_ui(&ui, &portraits, character);
+                        update_streak_display(
+                            &ui,
+                            character,
+                            current_streak_idx.load(Ordering::Relaxed),
+                        );
+                    }
+                }
+                drop(list);
+                refresh_killer_list();
+                refresh_summary();
+                publish_streak();
+            }
+            log::info!("Practice mode {}", if enabled { "on" } else { "off" });
+        }
+    });
+
+    // Applies a manual correction to the selected category. Corrections are undoable
+    // but are not match results, so they never reach the history log.
+    let edit_selected = {
+        let publish_streak = publish_streak.clone();
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        let push_undo = push_undo.clone();
+        let refresh_killer_list = refresh_killer_list.clone();
+        move |edit: &dyn Fn(&mut StreakCategory)| {
+            let mut list = lock(&characters);
+            let char_idx = current_char_idx.load(Ordering::Relaxed);
+            let s_idx = current_streak_idx.load(Ordering::Relaxed);
+            let Some(character) = list.get_mut(char_idx) else {
+                return;
+            };
+            let Some(cat) = character.streaks.get_mut(s_idx) else {
+                return;
+            };
+            let change = StreakChange::capture(char_idx, s_idx, cat);
+            edit(cat);
+            if *cat == change.previous {
+                return;
+            }
+            if let Some(ui) = ui_weak.upgrade() {
+                update_streak_display(&ui, character, s_idx);
+            }
+            drop(list);
+            push_undo(vec![change]);
+            autosave.mark_dirty();
+            refresh_killer_list();
+            publish_streak();
+        }
+    };
+
+    ui.on_frozen_toggled({
+        let e = edit_selected.clone();
+        move |frozen| e(&move |cat: &mut StreakCategory| cat.frozen = frozen)
+    });
+
+    ui.on_decrement({
+        let e = edit_selected.clone();
+        let config = config.clone();
+        move || {
+            let limits = config.borrow().counter;
+            e(&|cat| cat.set_current(cat.current - 1, &limits))
+        }
+    });
+
+    ui.on_set_counter({
+        let e = edit_selected.clone();
+        let config = config.clone();
+        move |value| {
+            let limits = config.borrow().counter;
+            e(&|cat| cat.set_current(value, &limits))
+        }
+    });
+
+    ui.on_set_goal({
+        let e = edit_selected.clone();
+        move |value| {
+            let goal = (value > 0).then_some(value);
+            e(&|cat| cat.goal = goal)
+        }
+    });
+
+    // Notes are saved as they're typed and, not being results, stay out of undo.
+    ui.on_set_note({
+        let autosave = autosave.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        move |note| {
+            let mut list = lock(&characters);
+            if let Some(cat) = list
+                .get_mut(current_char_idx.load(Ordering::Relaxed))
+                .and_then(|c| {
+                    c.streaks
+                        .get_mut(current_streak_idx.load(Ordering::Relaxed))
+                })
+            {
+                cat.note = note.to_string();
+                drop(list);
+                autosave.mark_dirty();
+            }
+        }
+    });
+
+    // An empty color clears the accent; one that doesn't parse is ignored.
+    ui.on_set_color({
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        let refuse_in_practice = refuse_in_practice.clone();
+        move |text, for_category| {
+            if refuse_in_practice() {
+                return;
+            }
+            let color = if text.trim().is_empty() {
+                None
+            } else if let Some(color) = parse_hex_color(&text) {
+                Some(format!(
+                    "#{:02x}{:02x}{:02x}",
+                    color.red(),
+                    color.green(),
+                    color.blue()
+                ))
+            } else {
+                log::warn!("Not a color: {}", text);
+                return;
+            };
+            let mut list = lock(&characters);
+            let streak_idx = current_streak_idx.load(Ordering::Relaxed);
+            let Some(character) = list.get_mut(current_char_idx.load(Ordering::Relaxed)) else {
+                return;
+            };
+            if for_category {
+                let Some(cat) = character.streaks.get_mut(streak_idx) else {
+                    return;
+                };
+                cat.color = color;
+            } else {
+                character.color = color;
+            }
+            if let Some(ui) = ui_weak.upgrade() {
+                update_streak_display(&ui, character, streak_idx);
+            }
+            drop(list);
+            autosave.mark_dirty();
+        }
+    });
+
+    ui.on_reset_session({
+        let ui_weak = ui.as_weak();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        move || {
+            let mut list = lock(&characters);
+            start_session(&mut list);
+            if let (Some(ui), Some(character)) = (
+                ui_weak.upgrade(),
+                list.get(current_char_idx.load(Ordering::Relaxed)),
+            ) {
+                update_streak_display(&ui, character, current_streak_idx.load(Ordering::Relaxed));
+            }
+        }
+    });
+
+    ui.on_reset_all_current({
+        let config = config.clone();
+        let publish_streak = publish_streak.clone();
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        let push_undo = push_undo.clone();
+        let refresh_killer_list = refresh_killer_list.clone();
+        move || {
+            let mut list = lock(&characters);
+            let action = reset_all_current(&mut list, &config.borrow().counter);
+            if let (Some(ui), Some(character)) = (
+                ui_weak.upgrade(),
+                list.get(current_char_idx.load(Ordering::Relaxed)),
+            ) {
+                update_streak_display(&ui, character, current_streak_idx.load(Ordering::Relaxed));
+            }
+            drop(list);
+            push_undo(action);
+            autosave.mark_dirty();
+            refresh_killer_list();
+            publish_streak();
+        }
+    });
+
+    ui.on_rename_character({
+        let publish_streak = publish_streak.clone();
+        let save_session = save_session.clone();
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        let portraits = portraits.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let undo_stack = undo_stack.clone();
+        let redo_stack = redo_stack.clone();
+        let killer_list = killer_list.clone();
+        let refuse_in_practice = refuse_in_practice.clone();
+        move |old, new| {
+            if refuse_in_practice() {
+                return;
+            }
+            let new = new.trim();
+            if new.is_empty() {
+                return;
+            }
+            let mut list = lock(&characters);
+            // Refuse to create a second character with the same name.
+            if killer_list.borrow().index_of(new).is_some() {
+                log::warn!("A character named {} already exists", new);
+                return;
+            }
+            let Some(idx) = killer_list.borrow().index_of(&old) else {
+                return;
+            };
+            let Some(character) = list.get_mut(idx) else {
+                return;
+            };
+            character.name = new.to_string();
+
+            // Re-sorting moves characters around, so keep every stored index pointing
+            // at the same character.
+            let remap = sort_characters(&mut list);
+            let selected = remap[current_char_idx.load(Ordering::Relaxed)];
+            current_char_idx.store(selected, Ordering::Relaxed);
+            for stack in [&undo_stack, &redo_stack] {
+                remap_characters(&mut stack.borrow_mut(), &remap);
+            }
+
+            if let Some(ui) = ui_weak.upgrade() {
+                let mut killers = killer_list.borrow_mut();
+                killers.rebuild(&list);
+                update_totals(&ui, &list);
+                killers.show(&ui, &list, selected);
+                if let Some(character) = list.get(selected) {
+                    update_ui(&ui, &portraits, character);
+                }
+            }
+            drop(list);
+            autosave.mark_dirty();
+            save_session();
+            publish_streak();
+        }
+    });
+
+    ui.on_reset_character_current({
+        let config = config.clone();
+        let publish_streak = publish_streak.clone();
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        let push_undo = push_undo.clone();
+        let refresh_killer_list = refresh_killer_list.clone();
+        move || {
+            let mut list = lock(&characters);
+            let char_idx = current_char_idx.load(Ordering::Relaxed);
+            let Some(character) = list.get_mut(char_idx) else {
+                return;
+            };
+            let action = reset_current(character, char_idx, &config.borrow().counter);
+            if let Some(ui) = ui_weak.upgrade() {
+                update_streak_display(&ui, character, current_streak_idx.load(Ordering::Relaxed));
+            }
+            drop(list);
+            push_undo(action);
+            autosave.mark_dirty();
+            refresh_killer_list();
+            publish_streak();
+        }
+    });
+
+    ui.on_delete_character({
+        let publish_streak = publish_streak.clone();
+        let save_session = save_session.clone();
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        let portraits = portraits.clone();
+        let media_root = media_root.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        let undo_stack = undo_stack.clone();
+        let redo_stack = redo_stack.clone();
+        let killer_list = killer_list.clone();
+        let refuse_in_practice = refuse_in_practice.clone();
+        move || {
+            if refuse_in_practice() {
+                return;
+            }
+            let mut list = lock(&characters);
+            // The window always needs a character to show and record against.
+            if list.len() <= 1 {
+                return;
+            }
+            let char_idx = current_char_idx.load(Ordering::Relaxed);
+            if char_idx >= list.len() {
+                return;
+            }
+            let removed = list.remove(char_idx);
+            for stack in [&undo_stack, &redo_stack] {
+                forget_character(&mut stack.borrow_mut(), char_idx);
+            }
+            // A portrait left in media brings the character back on the next scan.
+            let status = if !removed.image_path.is_empty()
+                && media_root.join(&removed.image_path).exists()
+            {
+                log::info!(
+                    "Deleted {}; its portrait {} is still in media",
+                    removed.name,
+                    removed.image_path
+                );
+                format!(
+                    "Deleted {}. Its portrait is still in media, so it comes back when media is next scanned; hide it to keep it out of the list.",
+                    removed.name
+                )
+            } else {
+                log::info!("Deleted {}", removed.name);
+                format!("Deleted {}.", removed.name)
+            };
+
+            let selected = char_idx.min(list.len() - 1);
+            current_char_idx.store(selected, Ordering::Relaxed);
+            current_streak_idx.store(0, Ordering::Relaxed);
+            if let Some(ui) = ui_weak.upgrade() {
+                let mut killers = killer_list.borrow_mut();
+                killers.rebuild(&list);
+                update_totals(&ui, &list);
+                killers.show(&ui, &list, selected);
+                update_ui(&ui, &portraits, &list[selected]);
+                update_streak_display(&ui, &list[selected], 0);
+                ui.set_can_undo(!undo_stack.borrow().is_empty());
+                ui.set_can_redo(!redo_stack.borrow().is_empty());
+                ui.set_character_status(status.into());
+            }
+            drop(list);
+            autosave.mark_dirty();
+            save_session();
+            publish_streak();
+        }
+    });
+
+    ui.on_add_category({
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        let portraits = portraits.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        move |name| {
+            let name = name.trim();
+            if name.is_empty() {
+                return;
+            }
+            let mut list = lock(&characters);
+            let Some(character) = list.get_mut(current_char_idx.load(Ordering::Relaxed)) else {
+                return;
+            };
+            if !ensure_categories(character, &[name.to_string()]) {
+                return;
+            }
+            if let Some(ui) = ui_weak.upgrade() {
+                update_ui(&ui, &portraits, character);
+                update_streak_display(&ui, character, current_streak_idx.load(Ordering::Relaxed));
+                update_totals(&ui, &list);
+            }
+            drop(list);
+            autosave.mark_dirty();
+        }
+    });
+
+    // Adds one category to the whole roster, e.g. for a new season's challenge.
+    // The category is also written to the category files, so new characters get it too.
+    ui.on_add_category_to_all({
+        let data_dir = data_dir.clone();
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        let portraits = portraits.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        move |name, include_survivors| {
+            let name = name.trim();
+            if name.is_empty() {
+                return;
+            }
+            let mut list = lock(&characters);
+            let categories = [name.to_string()];
+            let affected = list
+                .iter_mut()
+                .filter(|c| include_survivors || c.role == Role::Killer)
+                .map(|c| ensure_categories(c, &categories))
+                .filter(|&added| added)
+                .count();
+            log::info!("Added {} to {} characters", name, affected);
+            if let Some(ui) = ui_weak.upgrade() {
+                ui.set_category_status(format!("Added {} to {} characters", name, affected).into());
+                if let Some(character) = list.get(current_char_idx.load(Ordering::Relaxed)) {
+                    update_ui(&ui, &portraits, character);
+                    update_streak_display(
+                        &ui,
+                        character,
+                        current_streak_idx.load(Ordering::Relaxed),
+                    );
+                }
+                update_totals(&ui, &list);
+            }
+            drop(list);
+            if affected > 0 {
+                autosave.mark_dirty();
+            }
+            let dir = data_dir.borrow();
+            let files = [KILLER_STREAKS_FILE, SURVIVOR_STREAKS_FILE];
+            for file in &files[..if include_survivors { 2 } else { 1 }] {
+                let path = dir.join(file);
+                if let Err(e) = append_categories_to_file(&path, &categories) {
+                    log::warn!("Could not add {} to {}: {}", name, path.display(), e);
+                }
+            }
+        }
+    });
+
+    // Categories still listed in the category files come back on the next launch.
+    ui.on_remove_category({
+        let publish_streak = publish_streak.clone();
+        let save_session = save_session.clone();
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        let portraits = portraits.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        let undo_stack = undo_stack.clone();
+        let redo_stack = redo_stack.clone();
+        move |name| {
+            let mut list = lock(&characters);
+            let char_idx = current_char_idx.load(Ordering::Relaxed);
+            let Some(character) = list.get_mut(char_idx) else {
+                return;
+            };
+            // Every character keeps at least one category to record against.
+            if character.streaks.len() <= 1 {
+                return;
+            }
+            let Some(pos) = character
+                .streaks
+                .iter()
+                .position(|s| s.name == name.as_str())
+            else {
+                return;
+            };
+            character.streaks.remove(pos);
+            for stack in [&undo_stack, &redo_stack] {
+                forget_category(&mut stack.borrow_mut(), char_idx, pos);
+            }
+
+            let mut selected = current_streak_idx.load(Ordering::Relaxed);
+            if selected > pos {
+                selected -= 1;
+            }
+            selected = selected.min(character.streaks.len() - 1);
+            current_streak_idx.store(selected, Ordering::Relaxed);
+            if let Some(ui) = ui_weak.upgrade() {
+                update_ui(&ui, &portraits, character);
+                update_streak_display(&ui, character, selected);
+                update_totals(&ui, &list);
+                ui.set_can_undo(!undo_stack.borrow().is_empty());
+                ui.set_can_redo(!redo_stack.borrow().is_empty());
+            }
+            drop(list);
+            autosave.mark_dirty();
+            save_session();
+            publish_streak();
+        }
+    });
+
+    ui.on_find_unlisted_categories({
+        let data_dir = data_dir.clone();
+        let ui_weak = ui.as_weak();
+        let characters = characters.clone();
+        move || {
+            let (killer_cats, survivor_cats) = load_all_categories(&data_dir.borrow());
+            let list = lock(&characters);
+            let lines = describe_unlisted(&list, &killer_cats, &survivor_cats);
+            let count = list
+                .iter()
+                .map(|c| unlisted_categories(c, &killer_cats, &survivor_cats).len())
+                .sum::<usize>();
+            drop(list);
+            if let Some(ui) = ui_weak.upgrade() {
+                ui.set_unlisted_count(count as i32);
+                ui.set_category_status(if lines.is_empty() {
+                    "Every category is in the category files".into()
+                } else {
+                    lines.join("\n").into()
+                });
+            }
+        }
+    });
+
+    // Deletes what "Find unlisted" showed, except that every character keeps at least
+    // one category.
+    ui.on_remove_unlisted_categories({
+        let data_dir = data_dir.clone();
+        let publish_streak = publish_streak.clone();
+        let save_session = save_session.clone();
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        let portraits = portraits.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        let undo_stack = undo_stack.clone();
+        let redo_stack = redo_stack.clone();
+        move || {
+            let (killer_cats, survivor_cats) = load_all_categories(&data_dir.borrow());
+            let mut list = lock(&characters);
+            let selected_char = current_char_idx.load(Ordering::Relaxed);
+            let mut selected = current_streak_idx.load(Ordering::Relaxed);
+            let mut removed = 0;
+            for (char_idx, character) in list.iter_mut().enumerate() {
+                let mut unlisted = unlisted_categories(character, &killer_cats, &survivor_cats);
+                if unlisted.len() == character.streaks.len() {
+                    unlisted.remove(0);
+                }
+                for &pos in unlisted.iter().rev() {
+                    let cat = character.streaks.remove(pos);
+                    log::info!(
+                        "Removed {} {} (best {})",
+                        character.name,
+                        cat.name,
+                        cat.best
+                    );
+                    for stack in [&undo_stack, &redo_stack] {
+                        forget_category(&mut stack.borrow_mut(), char_idx, pos);
+                    }
+                    if char_idx == selected_char && selected > pos {
+                        selected -= 1;
+                    }
+                    removed += 1;
+                }
+            }
+            if let Some(character) = list.get(selected_char) {
+                selected = selected.min(character.streaks.len() - 1);
+                current_streak_idx.store(selected, Ordering::Relaxed);
+            }
+            if let Some(ui) = ui_weak.upgrade() {
+                if let Some(character) = list.get(selected_char) {
+                    update_ui(&ui, &portraits, character);
+                    update_streak_display(&ui, character, selected);
+                }
+                update_totals(&ui, &list);
+                ui.set_can_undo(!undo_stack.borrow().is_empty());
+                ui.set_can_redo(!redo_stack.borrow().is_empty());
+                ui.set_unlisted_count(0);
+                ui.set_category_status(format!("Removed {} categories", removed).into());
+            }
+            drop(list);
+            if removed > 0 {
+                autosave.mark_dirty();
+                save_session();
+                publish_streak();
+            }
+        }
+    });
+
+    // Swaps the selected category with its neighbour; the selection follows it.
+    let move_category = {
+        let publish_streak = publish_streak.clone();
+        let save_session = save_session.clone();
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        let portraits = portraits.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        let undo_stack = undo_stack.clone();
+        let redo_stack = redo_stack.clone();
+        move |up: bool| {
+            let mut list = lock(&characters);
+            let char_idx = current_char_idx.load(Ordering::Relaxed);
+            let Some(character) = list.get_mut(char_idx) else {
+                return;
+            };
+            let from = current_streak_idx.load(Ordering::Relaxed);
+            let to = if up {
+                from.checked_sub(1)
+            } else {
+                Some(from + 1).filter(|&i| i < character.streaks.len())
+            };
+            let Some(to) = to else {
+                return;
+            };
+            character.streaks.swap(from, to);
+            for stack in [&undo_stack, &redo_stack] {
+                swap_category(&mut stack.borrow_mut(), char_idx, from, to);
+            }
+            current_streak_idx.store(to, Ordering::Relaxed);
+            if let Some(ui) = ui_weak.upgrade() {
+                update_ui(&ui, &portraits, character);
+                update_streak_display(&ui, character, to);
+            }
+            drop(list);
+            autosave.mark_dirty();
+            save_session();
+            publish_streak();
+        }
+    };
+
+    ui.on_move_category_up({
+        let m = move_category.clone();
+        move || m(true)
+    });
+
+    ui.on_move_category_down({
+        let m = move_category.clone();
+        move || m(false)
+    });
+
+    // Global hotkeys go through the same path as the buttons.
+    let hotkeys = hotkeys::Hotkeys::register(&config.borrow().hotkeys);
+    let hotkey_timer = slint::Timer::default();
+    if let Some(hotkeys) = hotkeys {
+        let r = record_one.clone();
+        let request_loss = request_loss.clone();
+        hotkey_timer.start(
+            slint::TimerMode::Repeated,
+            Duration::from_millis(50),
+            move || {
+                for action in hotkeys.poll() {
+                    match action {
+                        hotkeys::HotkeyAction::Win => r(true),
+                        hotkeys::HotkeyAction::Loss => request_loss(),
+                    }
+                }
+            },
+        );
+    }
+
+    if config.borrow().http.enabled {
+        http::start(
+            &config.borrow().http,
+            characters.clone(),
+            http_current.clone(),
+        );
+    }
+
+    if config.borrow().twitch.enabled {
+        twitch::start(
+            &config.borrow().twitch,
+            characters.clone(),
+            current_char_idx.clone(),
+            current_streak_idx.clone(),
+        );
+    }
+
+    // With a tray icon, closing or minimising the window hides it there instead of
+    // quitting; the tray menu records results for the selected killer.
+    let tray = tray::Tray::new();
+    let has_tray = tray.is_some();
+    let tray_timer = slint::Timer::default();
+    if let Some(tray) = tray {
+        let r = record_one.clone();
+        let request_loss = request_loss.clone();
+        let ui_weak = ui.as_weak();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        tray_timer.start(
+            slint::TimerMode::Repeated,
+            Duration::from_millis(100),
+            move || {
+                let Some(ui) = ui_weak.upgrade() else {
+                    return;
+                };
+                if ui.window().is_visible() && ui.window().is_minimized() {
+                    if let Err(e) = ui.hide() {
+                        log::warn!("Could not hide the window: {}", e);
+                    }
+                }
+                for action in tray.poll() {
+                    match action {
+                        tray::TrayAction::Win => r(true),
+                        tray::TrayAction::Loss => request_loss(),
+                        tray::TrayAction::Show => {
+                            ui.window().set_minimized(false);
+                            if let Err(e) = ui.show() {
+                                log::warn!("Could not show the window: {}", e);
+                            }
+                        }
+                        tray::TrayAction::Quit => {
+                            if let Err(e) = slint::quit_event_loop() {
+                                log::warn!("Could not quit: {}", e);
+                            }
+                        }
+                    }
+                }
+                let list = lock(&characters);
+                if let Some(character) = list.get(current_char_idx.load(Ordering::Relaxed)) {
+                    tray.set_status(&overlay::render(
+                        "{name} {category}: {current} (PB {best})",
+                        character,
+                        current_streak_idx.load(Ordering::Relaxed),
+                    ));
+                }
+            },
+        );
+    }
+
+    // Moves one action from the `from` stack to the `to` stack, restoring its snapshot.
+    let step_history = {
+        let publish_streak = publish_streak.clone();
+        let save_session = save_session.clone();
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        let portraits = portraits.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        let undo_stack = undo_stack.clone();
+        let redo_stack = redo_stack.clone();
+        let killer_list = killer_list.clone();
+        let refresh_killer_list = refresh_killer_list.clone();
+        move |is_undo: bool| {
+            let (from, to) = if is_undo {
+                (&undo_stack, &redo_stack)
+            } else {
+                (&redo_stack, &undo_stack)
+            };
+            let mut list = lock(&characters);
+            let Some(action) = from.borrow_mut().pop() else {
+                return;
+            };
+            let inverse = apply_action(&mut list, &action);
+
+            // Jump to the category the action was recorded on.
+            if let (Some(first), Some(ui)) = (action.first(), ui_weak.upgrade()) {
+                if let Some(character) = list.get(first.char_idx) {
+                    current_char_idx.store(first.char_idx, Ordering::Relaxed);
+                    current_streak_idx.store(first.streak_idx, Ordering::Relaxed);
+                    update_ui(&ui, &portraits, character);
+                    update_streak_display(&ui, character, first.streak_idx);
+                    killer_list.borrow().select(&ui, first.char_idx);
+                }
+            }
+
+            {
+                let mut to = to.borrow_mut();
+                to.push(inverse);
+                if to.len() > UNDO_LIMIT {
+                    to.remove(0);
+                }
+            }
+            if let Some(ui) = ui_weak.upgrade() {
+                ui.set_can_undo(!undo_stack.borrow().is_empty());
+                ui.set_can_redo(!redo_stack.borrow().is_empty());
+            }
+
+            drop(list);
+            autosave.mark_dirty();
+            save_session();
+            publish_streak();
+            refresh_killer_list();
+        }
+    };
+
+    ui.on_undo({
+        let s = step_history.clone();
+        move || s(true)
+    });
+
+    ui.on_redo({
+        let s = step_history.clone();
+        move || s(false)
+    });
+
+    // Pick up portraits dropped into `media` while the app is running, including
+    // subfolders created after startup.
+    let media_watcher = watch::DebouncedWatcher::new(
+        &[&media_root.join(MEDIA_DIR)],
+        watch::RecursiveMode::Recursive,
+    )
+    .map_err(|e| log::warn!("Could not watch media: {}", e))
+    .ok();
+    // Adds characters for portraits in `media` that don't have one yet, selecting the
+    // one called `select` if it was among them. Returns whether any were added.
+    let add_new_portraits = {
+        let config = config.clone();
+        let data_dir = data_dir.clone();
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        let media_root = media_root.clone();
+        let portraits = portraits.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        let undo_stack = undo_stack.clone();
+        let redo_stack = redo_stack.clone();
+        let killer_list = killer_list.clone();
+        let practice = practice.clone();
+        move |select: Option<&str>| {
+            let mut list = lock(&characters);
+            let (killer_cats, survivor_cats) = load_all_categories(&data_dir.borrow());
+            let was_empty = list.is_empty();
+            let known = list.len();
+            if !scan_media(&media_root, &mut list, &killer_cats, &survivor_cats) {
+                return false;
+            }
+            // New portraits are real characters, so they outlive practice mode too.
+            if let Some(real) = practice.borrow_mut().as_mut() {
+                scan_media(&media_root, real, &killer_cats, &survivor_cats);
+                sort_characters(real);
+            }
+            // Only the new portraits are resized; files already in use stay as they are.
+            let config = config.borrow();
+            if config.portraits.resize {
+                for character in &list[known..] {
+                    let path = media_root.join(&character.image_path);
+                    match portraits::shrink(&path, config.portraits.max_size) {
+                        Ok(true) => log::info!("Resized {}", path.display()),
+                        Ok(false) => {}
+                        Err(e) => log::warn!("Keeping {} as it is: {}", path.display(), e),
+                    }
+                }
+            }
+            drop(config);
+
+            let remap = sort_characters(&mut list);
+            for stack in [&undo_stack, &redo_stack] {
+                remap_characters(&mut stack.borrow_mut(), &remap);
+            }
+            let picked = select.and_then(|name| list.iter().position(|c| c.name == name));
+            let selected = match picked {
+                Some(idx) => idx,
+                None if was_empty => 0,
+                None => remap[current_char_idx.load(Ordering::Relaxed)],
+            };
+            current_char_idx.store(selected, Ordering::Relaxed);
+            if picked.is_some() {
+                current_streak_idx.store(0, Ordering::Relaxed);
+            }
+
+            if let Some(ui) = ui_weak.upgrade() {
+                let mut killers = killer_list.borrow_mut();
+                killers.rebuild(&list);
+                update_totals(&ui, &list);
+                killers.show(&ui, &list, selected);
+                if let Some(character) = list.get(selected) {
+                    update_ui(&ui, &portraits, character);
+                    if was_empty || picked.is_some() {
+                        update_streak_display(&ui, character, 0);
+                    }
+                }
+            }
+            drop(list);
+            autosave.mark_dirty();
+            true
+        }
+    };
+
+    let media_timer = slint::Timer::default();
+    if let Some(watcher) = media_watcher {
+        let media_root = media_root.clone();
+        let portraits = portraits.clone();
+        let characters = characters.clone();
+        let add_new_portraits = add_new_portraits.clone();
+        media_timer.start(
+            slint::TimerMode::Repeated,
+            Duration::from_millis(250),
+            move || {
+                let Some(paths) = watcher.poll() else {
+                    return;
+                };
+                // A changed file may be a portrait that's already cached.
+                portraits.clear();
+                // Removed portraits only get a warning; their streaks stay saved.
+                for character in lock(&characters).iter() {
+                    let image = Path::new(&character.image_path);
+                    if paths.iter().any(|p| p.ends_with(image)) && !media_root.join(image).exists()
+                    {
+                        log::warn!(
+                            "Portrait for {} was removed; keeping its streaks",
+                            character.name
+                        );
+                    }
+                }
+                add_new_portraits(None);
+            },
+        );
+    }
+
+    // The monitors are only known once the window exists, so a window restored onto
+    // one that's since been unplugged is brought back as soon as the loop starts.
+    let placement_timer = slint::Timer::default();
+    if config.borrow().window.is_some() {
+        placement_timer.start(slint::TimerMode::SingleShot, Duration::ZERO, {
+            let ui_weak = ui.as_weak();
+            move || {
+                if let Some(ui) = ui_weak.upgrade() {
+                    ui.window().with_winit_window(keep_on_screen);
+                }
+            }
+        });
+    }
+
+    let save_geometry = {
+        let config = config.clone();
+        let config_dir = config_dir.clone();
+        move || {
+            if let Err(e) = config::save(&config_dir, &config.borrow()) {
+                log::warn!("Could not save {}: {}", config::CONFIG_FILE, e);
+            }
+        }
+    };
+    let geometry_timer = Rc::new(slint::Timer::default());
+
+    // Images dragged onto the window are copied into `media` and added straight away.
+    // Moves and resizes are remembered for the next start.
+    ui.window().on_winit_window_event({
+        let ui_weak = ui.as_weak();
+        let config = config.clone();
+        let media_root = media_root.clone();
+        let characters = characters.clone();
+        let publish_streak = publish_streak.clone();
+        let save_session = save_session.clone();
+        let save_geometry = save_geometry.clone();
+        let geometry_timer = geometry_timer.clone();
+        move |window, event| {
+            if let winit::event::WindowEvent::Moved(_) | winit::event::WindowEvent::Resized(_) =
+                event
+            {
+                // Keep the normal geometry, not that of a minimized or maximized window.
+                if window.is_minimized() || window.is_maximized() || window.is_fullscreen() {
+                    return WinitWindowEventResult::Propagate;
+                }
+                let mut config = config.borrow_mut();
+                let position = window.position();
+                let mut geometry = config.window.unwrap_or(config::WindowGeometry {
+                    x: position.x,
+                    y: position.y,
+                    width: window.size().width,
+                    height: window.size().height,
+                });
+                match *event {
+                    winit::event::WindowEvent::Moved(position) => {
+                        geometry.x = position.x;
+                        geometry.y = position.y;
+                    }
+                    winit::event::WindowEvent::Resized(size) if size.width > 0 => {
+                        geometry.width = size.width;
+                        geometry.height = size.height;
+                    }
+                    _ => {}
+                }
+                if config.window != Some(geometry) {
+                    config.window = Some(geometry);
+                    geometry_timer.start(
+                        slint::TimerMode::SingleShot,
+                        GEOMETRY_SAVE_DELAY,
+                        save_geometry.clone(),
+                    );
+                }
+                return WinitWindowEventResult::Propagate;
+            }
+            let winit::event::WindowEvent::DroppedFile(file) = event else {
+                return WinitWindowEventResult::Propagate;
+            };
+            let imported = import_portrait(&media_root, file, &lock(&characters));
+            let status = match imported {
+                Ok(name) if add_new_portraits(Some(&name)) => {
+                    save_session();
+                    publish_streak();
+                    format!("Added {}", name)
+                }
+                Ok(name) => format!("Could not add {}", name),
+                Err(e) => e,
+            };
+            if let Some(ui) = ui_weak.upgrade() {
+                ui.set_drop_status(status.into());
+            }
+            WinitWindowEventResult::PreventDefault
+        }
+    });
+
+    // Apply edits to the category and promotion files without a restart. The directory
+    // is watched rather than the files, since editors often replace a file instead of
+    // writing it.
+    let categories_watcher = Rc::new(RefCell::new(watch_categories(&data_dir.borrow())));
+    let categories_timer = slint::Timer::default();
+    categories_timer.start(slint::TimerMode::Repeated, Duration::from_millis(250), {
+        let data_dir = data_dir.clone();
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        let portraits = portraits.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        let categories_watcher = categories_watcher.clone();
+        let promotions = promotions.clone();
+        move || {
+            if let Some(ui) = ui_weak.upgrade() {
+                ui.set_external_edit(autosave.has_conflict());
+            }
+            let Some(paths) = categories_watcher.borrow().as_ref().and_then(|w| w.poll()) else {
+                return;
+            };
+            if paths
+                .iter()
+                .any(|p| p.file_name().is_some_and(|n| n == STREAKS_FILE))
+            {
+                autosave.check_for_external_edit();
+            }
+            if paths.iter().any(|p| {
+                p.file_name()
+                    .is_some_and(|n| n == promotions::PROMOTIONS_FILE)
+            }) {
+                *promotions.borrow_mut() = promotions::load(&data_dir.borrow());
+            }
+            let touched = paths.iter().any(|p| {
+                p.file_name()
+                    .is_some_and(|n| n == KILLER_STREAKS_FILE || n == SURVIVOR_STREAKS_FILE)
+            });
+            if !touched {
+                return;
+            }
+            let mut list = lock(&characters);
+            let (killer_cats, survivor_cats) = load_all_categories(&data_dir.borrow());
+            if !ensure_all_categories(&mut list, &killer_cats, &survivor_cats) {
+                return;
+            }
+            if let (Some(ui), Some(character)) = (
+                ui_weak.upgrade(),
+                list.get(current_char_idx.load(Ordering::Relaxed)),
+            ) {
+                update_ui(&ui, &portraits, character);
+                update_streak_display(&ui, character, current_streak_idx.load(Ordering::Relaxed));
+                update_totals(&ui, &list);
+            }
+            drop(list);
+            autosave.mark_dirty();
+        }
+    });
+
+    // Swaps every piece of per-profile state for the named profile's data.
+    let load_profile = {
+        let refresh_summary = refresh_summary.clone();
+        let publish_streak = publish_streak.clone();
+        let config = config.clone();
+        let media_root = media_root.clone();
+        let portraits = portraits.clone();
+        let data_root = data_root.clone();
+        let data_dir = data_dir.clone();
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        let undo_stack = undo_stack.clone();
+        let redo_stack = redo_stack.clone();
+        let killer_list = killer_list.clone();
+        let categories_watcher = categories_watcher.clone();
+        let promotions = promotions.clone();
+        let practice = practice.clone();
+        let config_dir = config_dir.clone();
+        move |name: &str| {
+            let dir = profile::profile_dir(&data_root, name);
+            // Finish writing the old profile before its data is replaced, or before
+            // the load is refused and it stays.
+            autosave.flush();
+            let loaded = match load_data(&dir, &media_root) {
+                Ok(loaded) => loaded,
+                Err(newer) => {
+                    if let Some(ui) = ui_weak.upgrade() {
+                        ui.set_profile_status(
+                            format!(
+                                "Not switching to {}: {}. Update the app to use it.",
+                                name, newer
+                            )
+                            .into(),
+                        );
+                        let profiles = profile::list_profiles(&data_root);
+                        let current = &config.borrow().profile;
+                        ui.set_selected_profile_index(
+                            profiles.iter().position(|p| p == current).unwrap_or(0) as i32,
+                        );
+                    }
+                    return;
+                }
+            };
+            // Practice results belong to the old profile; they go with it.
+            if practice.borrow_mut().take().is_some() {
+                autosave.resume();
+                if let Some(ui) = ui_weak.upgrade() {
+                    ui.set_practice_mode(false);
+                }
+            }
+            autosave.set_dir(dir.clone());
+            let mut list = lock(&characters);
+            *list = loaded;
+            *promotions.borrow_mut() = promotions::load(&dir);
+            let (char_idx, streak_idx) = session::load(&dir).resolve(&list);
+            *categories_watcher.borrow_mut() = watch_categories(&dir);
+            *data_dir.borrow_mut() = dir;
+            current_char_idx.store(char_idx, Ordering::Relaxed);
+            current_streak_idx.store(streak_idx, Ordering::Relaxed);
+            undo_stack.borrow_mut().clear();
+            redo_stack.borrow_mut().clear();
+
+            if let Some(ui) = ui_weak.upgrade() {
+                let mut killers = killer_list.borrow_mut();
+                killers.rebuild(&list);
+                update_totals(&ui, &list);
+                killers.show(&ui, &list, char_idx);
+                if let Some(character) = list.get(char_idx) {
+                    update_ui(&ui, &portraits, character);
+                    update_streak_display(&ui, character, streak_idx);
+                }
+                ui.set_can_undo(false);
+                ui.set_can_redo(false);
+                ui.set_profile_status("".into());
+
+                let profiles = profile::list_profiles(&data_root);
+                ui.set_selected_profile_index(
+                    profiles.iter().position(|p| p == name).unwrap_or(0) as i32
+                );
+                ui.set_profile_names(Rc::new(VecModel::from(profiles_model(&profiles))).into());
+            }
+
+            drop(list);
+            refresh_summary();
+            publish_streak();
+
+            let mut config = config.borrow_mut();
+            config.profile = name.to_string();
+            if let Err(e) = config::save(&config_dir, &config) {
+                log::warn!("Could not save {}: {}", config::CONFIG_FILE, e);
+            }
+        }
+    };
+
+    ui.on_profile_selected({
+        let l = load_profile.clone();
+        move |name| l(&name)
+    });
+
+    ui.on_create_profile({
+        let l = load_profile.clone();
+        move |name| {
+            let name = name.trim();
+            if !profile::is_valid_name(name) {
+                log::warn!("{:?} is not a valid profile name", name);
+                return;
+            }
+            l(name)
+        }
+    });
+
+    ui.on_export_report({
+        let data_dir = data_dir.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        move |all| {
+            let list = lock(&characters);
+            let result = if all {
+                report::write_all(&data_dir.borrow(), &list)
+            } else if let Some(character) = list.get(current_char_idx.load(Ordering::Relaxed)) {
+                report::write_character(&data_dir.borrow(), character)
+            } else {
+                return;
+            };
+            match result {
+                Ok(path) => log::info!("Wrote {}", path.display()),
+                Err(e) => log::warn!("Could not write the report: {}", e),
+            }
+        }
+    });
+
+    // Names of the characters on the stats card, in the order they were added.
+    let card_characters: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+    let show_card_characters = {
+        let card_characters = card_characters.clone();
+        let ui_weak = ui.as_weak();
+        move || {
+            if let Some(ui) = ui_weak.upgrade() {
+                let names: Vec<slint::SharedString> =
+                    card_characters.borrow().iter().map(Into::into).collect();
+                ui.set_card_characters(Rc::new(VecModel::from(names)).into());
+            }
+        }
+    };
+
+    ui.on_card_requested({
+        let card_characters = card_characters.clone();
+        let show_card_characters = show_card_characters.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let ui_weak = ui.as_weak();
+        move || {
+            let Some(ui) = ui_weak.upgrade() else {
+                return;
+            };
+            let list = lock(&characters);
+            if card_characters.borrow().is_empty() {
+                if let Some(character) = list.get(current_char_idx.load(Ordering::Relaxed)) {
+                    card_characters.borrow_mut().push(character.name.clone());
+                }
+            }
+            // Categories keep their tick across openings; new ones start ticked.
+            let old = ui.get_card_categories();
+            let mut picks: Vec<CardPick> = Vec::new();
+            for cat in list.iter().flat_map(|c| &c.streaks) {
+                if picks.iter().any(|p| p.name == cat.name.as_str()) {
+                    continue;
+                }
+                let picked = old
+                    .iter()
+                    .find(|p| p.name == cat.name.as_str())
+                    .is_none_or(|p| p.picked);
+                picks.push(CardPick {
+                    name: cat.name.clone().into(),
+                    picked,
+                });
+            }
+            drop(list);
+            ui.set_card_categories(Rc::new(VecModel::from(picks)).into());
+            ui.set_card_status("".into());
+            show_card_characters();
+        }
+    });
+
+    ui.on_card_add_character({
+        let card_characters = card_characters.clone();
+        let show_card_characters = show_card_characters.clone();
+        let ui_weak = ui.as_weak();
+        move |name| {
+            let Some(ui) = ui_weak.upgrade() else {
+                return;
+            };
+            let mut names = card_characters.borrow_mut();
+            if name.is_empty() || names.iter().any(|n| *n == name.as_str()) {
+                return;
+            }
+            if names.len() >= card::MAX_CHARACTERS {
+                ui.set_card_status(
+                    format!("The card fits {} characters", card::MAX_CHARACTERS).into(),
+                );
+                return;
+            }
+            names.push(name.to_string());
+            drop(names);
+            ui.set_card_status("".into());
+            show_card_characters();
+        }
+    });
+
+    ui.on_card_remove_character({
+        let card_characters = card_characters.clone();
+        let show_card_characters = show_card_characters.clone();
+        move |index| {
+            let mut names = card_characters.borrow_mut();
+            if index >= 0 && (index as usize) < names.len() {
+                names.remove(index as usize);
+            }
+            drop(names);
+            show_card_characters();
+        }
+    });
+
+    ui.on_card_category_toggled({
+        let ui_weak = ui.as_weak();
+        move |index, picked| {
+            let Some(ui) = ui_weak.upgrade() else {
+                return;
+            };
+            let picks = ui.get_card_categories();
+            if let Some(mut pick) = picks.row_data(index as usize) {
+                pick.picked = picked;
+                picks.set_row_data(index as usize, pick);
+            }
+        }
+    });
+
+    ui.on_export_card({
+        let card_characters = card_characters.clone();
+        let characters = characters.clone();
+        let data_dir = data_dir.clone();
+        let media_root = media_root.clone();
+        let ui_weak = ui.as_weak();
+        move || {
+            let Some(ui) = ui_weak.upgrade() else {
+                return;
+            };
+            // Copied out so portraits are read and drawn without holding the lock.
+            let picked: Vec<Character> = {
+                let list = lock(&characters);
+                card_characters
+                    .borrow()
+                    .iter()
+                    .filter_map(|name| list.iter().find(|c| c.name == *name).cloned())
+                    .collect()
+            };
+            let categories: Vec<String> = ui
+                .get_card_categories()
+                .iter()
+                .filter(|p| p.picked)
+                .map(|p| p.name.to_string())
+                .collect();
+            let status = match card::write(&data_dir.borrow(), &media_root, &picked, &categories) {
+                Ok(path) => format!("Saved {}", path.display()),
+                Err(e) => {
+                    log::warn!("Could not write the stats card: {}", e);
+                    format!("Could not save the card: {}", e)
+                }
+            };
+            ui.set_card_status(status.into());
+        }
+    });
+
+    ui.on_export_series({
+        let data_dir = data_dir.clone();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        move |selected_only| {
+            let only = if selected_only {
+                let list = lock(&characters);
+                let Some(character) = list.get(current_char_idx.load(Ordering::Relaxed)) else {
+                    return;
+                };
+                let Some(cat) = character
+                    .streaks
+                    .get(current_streak_idx.load(Ordering::Relaxed))
+                else {
+                    return;
+                };
+                Some((character.name.clone(), cat.name.clone()))
+            } else {
+                None
+            };
+            let dir = data_dir.borrow();
+            let entries = history::load(&dir);
+            let only = only.as_ref().map(|(c, s)| (c.as_str(), s.as_str()));
+            match series::write(&dir, &entries, only) {
+                Ok(path) => log::info!("Wrote {}", path.display()),
+                Err(e) => log::warn!("Could not write {}: {}", series::SERIES_FILE, e),
+            }
+        }
+    });
+
+    // Relative archive paths, and an empty one, are taken from the base directory.
+    let archive_path = {
+        let base_dir = base_dir.clone();
+        move |path: &str| match path.trim() {
+            "" => base_dir.join(archive::DEFAULT_ARCHIVE),
+            path => base_dir.join(path),
+        }
+    };
+
+    ui.on_export_archive({
+        let archive_path = archive_path.clone();
+        let media_root = media_root.clone();
+        let data_dir = data_dir.clone();
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        move |path, include_media| {
+            let path = archive_path(&path);
+            autosave.flush();
+            let status =
+                match archive::export(&path, &data_dir.borrow(), &media_root, include_media) {
+                    Ok(()) => format!("Exported to {}", path.display()),
+                    Err(e) => {
+                        log::warn!("Could not export {}: {}", path.display(), e);
+                        format!("Export failed: {}", e)
+                    }
+                };
+            if let Some(ui) = ui_weak.upgrade() {
+                ui.set_archive_status(status.into());
+            }
+        }
+    });
+
+    ui.on_import_archive({
+        let archive_path = archive_path.clone();
+        let l = load_profile.clone();
+        let config = config.clone();
+        let media_root = media_root.clone();
+        let data_dir = data_dir.clone();
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        move |path| {
+            let path = archive_path(&path);
+            autosave.flush();
+            let result = archive::import(&path, &data_dir.borrow(), &media_root);
+            let status = match result {
+                Ok(()) => {
+                    let name = config.borrow().profile.clone();
+                    l(&name);
+                    format!("Imported {}", path.display())
+                }
+                Err(e) => {
+                    log::warn!("Could not import {}: {}", path.display(), e);
+                    format!("Import failed: {}", e)
+                }
+            };
+            if let Some(ui) = ui_weak.upgrade() {
+                ui.set_archive_status(status.into());
+            }
+        }
+    });
+
+    // Characters read from the save being merged in, kept while its summary is shown.
+    let pending_merge: Rc<RefCell<Option<Vec<Character>>>> = Rc::new(RefCell::new(None));
+
+    ui.on_preview_merge({
+        let archive_path = archive_path.clone();
+        let pending_merge = pending_merge.clone();
+        let characters = characters.clone();
+        let ui_weak = ui.as_weak();
+        move |path| {
+            let Some(ui) = ui_weak.upgrade() else {
+                return;
+            };
+            let path = archive_path(&path);
+            let theirs = match merge::read(&path) {
+                Ok(theirs) => theirs,
+                Err(e) => {
+                    log::warn!("Could not read {}: {}", path.display(), e);
+                    ui.set_archive_status(format!("Merge failed: {}", e).into());
+                    return;
+                }
+            };
+            let current = if ui.get_merge_keep_theirs() {
+                merge::Current::Theirs
+            } else {
+                merge::Current::Ours
+            };
+            let lines = {
+                let list = lock(&characters);
+                merge::describe(&list, &merge::merge(&list, &theirs, current))
+            };
+            let mut summary = if lines.is_empty() {
+                format!("Merging {} would change nothing", path.display())
+            } else {
+                format!("Merging {} would change:", path.display())
+            };
+            for line in lines.iter().take(MERGE_SUMMARY_LINES) {
+                summary.push('\n');
+                summary.push_str(line);
+            }
+            if lines.len() > MERGE_SUMMARY_LINES {
+                summary.push_str(&format!(
+                    "\n...and {} more",
+                    lines.len() - MERGE_SUMMARY_LINES
+                ));
+            }
+            *pending_merge.borrow_mut() = Some(theirs);
+            ui.set_merge_ready(!lines.is_empty());
+            ui.set_merge_summary(summary.into());
+            ui.set_archive_status("".into());
+        }
+    });
+
+    ui.on_apply_merge({
+        let pending_merge = pending_merge.clone();
+        let publish_streak = publish_streak.clone();
+        let refresh_killer_list = refresh_killer_list.clone();
+        let data_dir = data_dir.clone();
+        let autosave = autosave.clone();
+        let portraits = portraits.clone();
+        let ui_weak = ui.as_weak();
+        let characters = characters.clone();
+        let current_char_idx = current_char_idx.clone();
+        let current_streak_idx = current_streak_idx.clone();
+        let undo_stack = undo_stack.clone();
+        let redo_stack = redo_stack.clone();
+        let practice = practice.clone();
+        move || {
+            let Some(ui) = ui_weak.upgrade() else {
+                return;
+            };
+            if practice.borrow().is_some() {
+                ui.set_archive_status("Leave practice mode before merging".into());
+                return;
+            }
+            let Some(theirs) = pending_merge.borrow_mut().take() else {
+                return;
+            };
+            let current = if ui.get_merge_keep_theirs() {
+                merge::Current::Theirs
+            } else {
+                merge::Current::Ours
+            };
+            // The file as it was before the merge stays restorable from the backups.
+            autosave.flush();
+            let dir = data_dir.borrow().clone();
+            let json = dir.join(STREAKS_FILE);
+            if let Err(e) = backup_save_file(&json, &dir.join(BACKUP_DIR)) {
+                log::warn!("Could not back up {}: {}", json.display(), e);
+            }
+            let mut list = lock(&characters);
+            let merged = merge::merge(&list, &theirs, current);
+            let changes = merge::describe(&list, &merged).len();
+            *list = merged;
+            // Merged characters keep their places, so the selection still fits; undo
+            // entries would put back values from before the merge.
+            undo_stack.borrow_mut().clear();
+            redo_stack.borrow_mut().clear();
+            ui.set_can_undo(false);
+            ui.set_can_redo(false);
+            if let Some(character) = list.get(current_char_idx.load(Ordering::Relaxed)) {
+                update_ui(&ui, &portraits, character);
+                update_streak_display(&ui, character, current_streak_idx.load(Ordering::Relaxed));
+            }
+            drop(list);
+            autosave.mark_dirty();
+            refresh_killer_list();
+            publish_streak();
+            ui.set_merge_summary("".into());
+            ui.set_archive_status(format!("Merged {} changes", changes).into());
+        }
+    });
+
+    ui.on_cancel_merge({
+        let pending_merge = pending_merge.clone();
+        let ui_weak = ui.as_weak();
+        move || {
+            pending_merge.borrow_mut().take();
+            if let Some(ui) = ui_weak.upgrade() {
+                ui.set_merge_summary("".into());
+            }
+        }
+    });
+
+    // streaks.json changed outside the app: take the file's version or overwrite it.
+    ui.on_reload_saves({
+        let l = load_profile.clone();
+        let config = config.clone();
+        move || {
+            let name = config.borrow().profile.clone();
+            l(&name)
+        }
+    });
+
+    ui.on_keep_saves({
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        move || {
+            autosave.keep_ours();
+            if let Some(ui) = ui_weak.upgrade() {
+                ui.set_external_edit(false);
+            }
+        }
+    });
+
+    // Edits a copy of every setting; only "Save" writes them back.
+    let preferences = PreferencesWindow::new()?;
+    ui.on_open_preferences({
+        let config = config.clone();
+        let preferences = preferences.as_weak();
+        move || {
+            let Some(preferences) = preferences.upgrade() else {
+                return;
+            };
+            let config = config.borrow();
+            preferences.set_dark_theme(config.theme == config::Theme::Dark);
+            preferences.set_language_names(
+                Rc::new(VecModel::from(
+                    LANGUAGES
+                        .iter()
+                        .map(|&(_, name)| slint::SharedString::from(name))
+                        .collect::<Vec<_>>(),
+                ))
+                .into(),
+            );
+            preferences.set_language_index(
+                LANGUAGES
+                    .iter()
+                    .position(|&(code, _)| code == config.language)
+                    .unwrap_or(0) as i32,
+            );
+            preferences.set_autosave_seconds(config.autosave.interval_secs as i32);
+            preferences.set_streak_cap(config.counter.max.unwrap_or(0));
+            preferences.set_frozen_wins(config.counter.frozen_wins);
+            preferences.set_confirm_big_losses(config.confirm_loss.enabled);
+            preferences.set_loss_confirm_threshold(config.confirm_loss.threshold);
+            preferences.set_milestones_enabled(config.milestones.enabled);
+            preferences.set_milestone_every(config.milestones.every);
+            preferences.set_milestone_sound(config.milestones.sound);
+            preferences.set_sound_enabled(config.sound.enabled);
+            preferences.set_keep_on_top(config.always_on_top);
+            preferences.set_start_with_windows(config.start_with_windows);
+            preferences.set_startup_supported(startup::SUPPORTED);
+            preferences.set_startup_error("".into());
+            preferences.set_discord_enabled(config.discord.enabled);
+            preferences.set_discord_application_id(config.discord.application_id.clone().into());
+            preferences.set_check_updates(config.updates.check);
+            preferences.set_resize_portraits(config.portraits.resize);
+            preferences.set_portrait_size(config.portraits.max_size as i32);
+            preferences.set_killer_win_label(config.buttons.killer_win.clone().into());
+            preferences.set_killer_loss_label(config.buttons.killer_loss.clone().into());
+            preferences.set_survivor_win_label(config.buttons.survivor_win.clone().into());
+            preferences.set_survivor_loss_label(config.buttons.survivor_loss.clone().into());
+            preferences.set_swap_result_buttons(config.buttons.swap);
+            preferences.set_win_hotkey(config.hotkeys.win.clone().into());
+            preferences.set_loss_hotkey(config.hotkeys.loss.clone().into());
+            preferences.set_data_dir(config.data_dir.clone().into());
+            if let Err(e) = preferences.show() {
+                log::warn!("Could not open preferences: {}", e);
+            }
+        }
+    });
+
+    preferences.on_save({
+        let publish_streak = publish_streak.clone();
+        let config = config.clone();
+        let config_dir = config_dir.clone();
+        let autosave = autosave.clone();
+        let ui_weak = ui.as_weak();
+        let preferences = preferences.as_weak();
+        move || {
+            let (Some(ui), Some(preferences)) = (ui_weak.upgrade(), preferences.upgrade()) else {
+                return;
+            };
+            let mut config = config.borrow_mut();
+            config.theme = if preferences.get_dark_theme() {
+                config::Theme::Dark
+            } else {
+                config::Theme::Light
+            };
+            let language = LANGUAGES
+                .get(preferences.get_language_index() as usize)
+                .map_or("", |&(code, _)| code);
+            if language != config.language {
+                config.language = language.to_string();
+                select_language(language);
+            }
+            config.autosave.interval_secs = preferences.get_autosave_seconds().max(1) as u64;
+            let cap = preferences.get_streak_cap();
+            config.counter.max = (cap > 0).then_some(cap);
+            config.counter.frozen_wins = preferences.get_frozen_wins();
+            config.confirm_loss.enabled = preferences.get_confirm_big_losses();
+            config.confirm_loss.threshold = preferences.get_loss_confirm_threshold();
+            config.milestones = config::MilestoneConfig {
+                enabled: preferences.get_milestones_enabled(),
+                every: preferences.get_milestone_every().max(1),
+                sound: preferences.get_milestone_sound(),
+            };
+            config.sound.enabled = preferences.get_sound_enabled();
+            config.always_on_top = preferences.get_keep_on_top();
+            let start_with_windows = preferences.get_start_with_windows();
+            let mut startup_error = None;
+            if start_with_windows != config.start_with_windows {
+                match startup::set_enabled(start_with_windows) {
+                    Ok(()) => config.start_with_windows = start_with_windows,
+                    Err(e) => {
+                        log::warn!("{}", e);
+                        preferences.set_start_with_windows(config.start_with_windows);
+                        startup_error = Some(e);
+                    }
+                }
+            }
+            config.discord.application_id =
+                preferences.get_discord_application_id().trim().to_string();
+            config.discord.enabled =
+                preferences.get_discord_enabled() && !config.discord.application_id.is_empty();
+            config.updates.check = preferences.get_check_updates();
+            config.portraits.resize = preferences.get_resize_portraits();
+            // An emptied label goes back to its default rather than leaving a blank button.
+            let defaults = config::ButtonConfig::default();
+            let label = |text: slint::SharedString, default: String| {
+                let text = text.trim();
+                if text.is_empty() {
+                    default
+                } else {
+                    text.to_string()
+                }
+            };
+            config.buttons = config::ButtonConfig {
+                killer_win: label(preferences.get_killer_win_label(), defaults.killer_win),
+                killer_loss: label(preferences.get_killer_loss_label(), defaults.killer_loss),
+                survivor_win: label(preferences.get_survivor_win_label(), defaults.survivor_win),
+                survivor_loss: label(
+                    preferences.get_survivor_loss_label(),
+                    defaults.survivor_loss,
+                ),
+                swap: preferences.get_swap_result_buttons(),
+            };
+            config.hotkeys.win = preferences.get_win_hotkey().trim().to_string();
+            config.hotkeys.loss = preferences.get_loss_hotkey().trim().to_string();
+            config.data_dir = preferences.get_data_dir().trim().to_string();
+            if let Err(e) = config::save(&config_dir, &config) {
+                log::warn!("Could not save {}: {}", config::CONFIG_FILE, e);
+            }
+
+            // Everything but the hotkeys and data folder applies right away.
+            ui.set_dark_theme(config.theme == config::Theme::Dark);
+            ui.set_sound_enabled(config.sound.enabled);
+            ui.set_keep_on_top(config.always_on_top);
+            ui.set_confirm_big_losses(config.confirm_loss.enabled);
+            update_button_labels(&ui, &config.buttons);
+            autosave.set_interval(config.autosave.interval_secs);
+            drop(config);
+            // Turns the Discord status on or off.
+            publish_streak();
+            // Stay open so a failed startup change can't go unnoticed.
+            if let Some(e) = startup_error {
+                preferences.set_startup_error(e.into());
+                return;
+            }
+            if let Err(e) = preferences.hide() {
+                log::warn!("Could not close preferences: {}", e);
+            }
+        }
+    });
+
+    preferences.on_cancel({
+        let preferences = preferences.as_weak();
+        move || {
+            if let Some(Err(e)) = preferences.upgrade().map(|p| p.hide()) {
+                log::warn!("Could not close preferences: {}", e);
+            }
+        }
+    });
+
+    if config.borrow().updates.check {
+        let ui_weak = ui.as_weak();
+        update::check(move |release| {
+            let shown = slint::invoke_from_event_loop(move || {
+                if let Some(ui) = ui_weak.upgrade() {
+                    ui.set_update_version(release.version.into());
+                    ui.set_update_url(release.url.into());
+                }
+            });
+            if let Err(e) = shown {
+                log::debug!("Could not show the update: {}", e);
+            }
+        });
+    }
+    ui.on_open_update({
+        let ui_weak = ui.as_weak();
+        move || {
+            if let Some(ui) = ui_weak.upgrade() {
+                update::open_in_browser(&ui.get_update_url());
+            }
+        }
+    });
+
+    let result = if has_tray {
+        ui.show().and_then(|_| slint::run_event_loop_until_quit())
+    } else {
+        ui.run()
+    };
+    // Whatever happened to the event loop, don't lose the last changes.
+    autosave.flush();
+    if geometry_timer.running() {
+        save_geometry();
+    }
+    result?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_name, load_categories_from_file};
+
+    #[test]
+    fn format_name_splits_camel_case() {
+        assert_eq!(format_name("TheTrapper"), "The Trapper");
+    }
+
+    #[test]
+    fn format_name_replaces_underscores() {
+        assert_eq!(format_name("the_pig"), "the pig");
+        assert_eq!(format_name("The_Huntress3"), "The Huntress 3");
+    }
+
+    #[test]
+    fn format_name_splits_digits_from_letters() {
+        assert_eq!(format_name("Nemesis2"), "Nemesis 2");
+        assert_eq!(format_name("Wave2Killer"), "Wave 2 Killer");
+    }
+
+    #[test]
+    fn format_name_keeps_acronyms_and_short_forms() {
+        assert_eq!(format_name("PH"), "PH");
+        assert_eq!(format_name("4k"), "4k");
+    }
+
+    #[test]
+    fn format_name_keeps_acronyms_together() {
+        assert_eq!(format_name("PHtheExecutioner"), "PH the Executioner");
+        assert_eq!(format_name("PHTheExecutioner"), "PH The Executioner");
+        assert_eq!(format_name("THENURSE"), "THENURSE");
+    }
+
+    #[test]
+    fn format_name_keeps_roman_numerals_attached() {
+        assert_eq!(format_name("TrapperIII"), "Trapper III");
+        assert_eq!(format_name("The_Trapper_IV"), "The Trapper IV");
+        assert_eq!(format_name("TrapperIIThe"), "Trapper II The");
+    }
+
+    #[test]
+    fn load_categories_strips_bom_and_line_endings() {
+        let path = std::env::temp_dir().join(format!("dbd-bom-{}.txt", std::process::id()));
+        std::fs::write(&path, "\u{feff}4k\r\n# comment\r\n3k\rPerkless\n").unwrap();
+        let categories = load_categories_from_file(&path, &["default"]);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(categories, ["4k", "3k", "Perkless"]);
+    }
 }
@@ -0,0 +1,80 @@
+//! Wins and losses per killer per map, from the map typed in with each result.
+use crate::{history::HistoryEntry, MapRow};
+use std::cmp::Ordering;
+
+/// Map shown for results recorded without one.
+const UNKNOWN_MAP: &str = "Unknown";
+
+/// Orders of the map table, as listed in its sort box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MapSort {
+    Killer,
+    Map,
+    /// Lowest win rate first, so the maps that end streaks lead.
+    WinRate,
+    /// Most games first.
+    Games,
+}
+
+impl MapSort {
+    pub const ALL: [MapSort; 4] = [
+        MapSort::Killer,
+        MapSort::Map,
+        MapSort::WinRate,
+        MapSort::Games,
+    ];
+}
+
+/// One row per character and map. Maps are matched ignoring case, shown as first
+/// typed.
+pub fn by_map(entries: &[HistoryEntry], sort: MapSort) -> Vec<MapRow> {
+    // (character, map, wins, losses), in the order first seen.
+    let mut buckets: Vec<(String, String, i32, i32)> = Vec::new();
+    for entry in entries {
+        let map = match entry.map.trim() {
+            "" => UNKNOWN_MAP,
+            map => map,
+        };
+        let bucket = match buckets
+            .iter_mut()
+            .find(|(c, m, ..)| *c == entry.character && m.eq_ignore_ascii_case(map))
+        {
+            Some(bucket) => bucket,
+            None => {
+                buckets.push((entry.character.clone(), map.to_string(), 0, 0));
+                buckets.last_mut().expect("just pushed")
+            }
+        };
+        if entry.win {
+            bucket.2 += 1;
+        } else {
+            bucket.3 += 1;
+        }
+    }
+
+    let mut rows: Vec<MapRow> = buckets
+        .into_iter()
+        .map(|(character, map, wins, losses)| MapRow {
+            character: character.into(),
+            map: map.into(),
+            wins,
+            losses,
+            win_rate: wins as f32 * 100.0 / (wins + losses) as f32,
+        })
+        .collect();
+    let by_name = |a: &MapRow, b: &MapRow| a.character.cmp(&b.character).then(a.map.cmp(&b.map));
+    rows.sort_by(|a, b| match sort {
+        MapSort::Killer => by_name(a, b),
+        MapSort::Map => a.map.cmp(&b.map).then(a.character.cmp(&b.character)),
+        MapSort::WinRate => a
+            .win_rate
+            .partial_cmp(&b.win_rate)
+            .unwrap_or(Ordering::Equal)
+            .then((b.wins + b.losses).cmp(&(a.wins + a.losses)))
+            .then_with(|| by_name(a, b)),
+        MapSort::Games => (b.wins + b.losses)
+            .cmp(&(a.wins + a.losses))
+            .then_with(|| by_name(a, b)),
+    });
+    rows
+}
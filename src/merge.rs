@@ -0,0 +1,151 @@
+//! Comparing two saves, say from a laptop and a desktop, and combining them without
+//! losing the best streak from either.
+use crate::{archive, schema, Character};
+use std::{error::Error, fs, path::Path};
+
+/// Whose current streak a merged category keeps. Best streaks always come from
+/// whichever save has the higher one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Current {
+    Ours,
+    Theirs,
+}
+
+/// A category whose current or best streak differs between two saves, as
+/// `(current, best)` from each. `None` means that save doesn't have it.
+pub struct Difference {
+    pub character: String,
+    pub category: String,
+    pub ours: Option<(i32, i32)>,
+    pub theirs: Option<(i32, i32)>,
+}
+
+/// The characters in the `streaks.json` at `path`, or in the one inside a backup zip,
+/// migrated if it's an older version.
+pub fn read(path: &Path) -> Result<Vec<Character>, Box<dyn Error>> {
+    if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+    {
+        return archive::read_saves(path);
+    }
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let (characters, _) = schema::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(characters)
+}
+
+/// Every category that differs, in `ours`' order followed by those only `theirs`
+/// has. Characters and categories are matched by name.
+pub fn differences(ours: &[Character], theirs: &[Character]) -> Vec<Difference> {
+    let mut found = Vec::new();
+    let mut add = |character: &str, category: &str, ours, theirs| {
+        if ours != theirs {
+            found.push(Difference {
+                character: character.to_string(),
+                category: category.to_string(),
+                ours,
+                theirs,
+            });
+        }
+    };
+    for character in ours {
+        let other = theirs.iter().find(|c| c.name == character.name);
+        for cat in &character.streaks {
+            let other_cat = other.and_then(|o| o.streaks.iter().find(|s| s.name == cat.name));
+            add(
+                &character.name,
+                &cat.name,
+                Some((cat.current, cat.best)),
+                other_cat.map(|s| (s.current, s.best)),
+            );
+        }
+    }
+    for character in theirs {
+        let other = ours.iter().find(|c| c.name == character.name);
+        for cat in &character.streaks {
+            if other.is_some_and(|o| o.streaks.iter().any(|s| s.name == cat.name)) {
+                continue;
+            }
+            add(
+                &character.name,
+                &cat.name,
+                None,
+                Some((cat.current, cat.best)),
+            );
+        }
+    }
+    found
+}
+
+/// `ours` with the higher best of each category taken from `theirs`, the current
+/// streaks picked by `current`, and the characters and categories only `theirs` has
+/// added at the end. Everything else stays ours.
+pub fn merge(ours: &[Character], theirs: &[Character], current: Current) -> Vec<Character> {
+    let mut merged = ours.to_vec();
+    for character in theirs {
+        let Some(mine) = merged.iter_mut().find(|c| c.name == character.name) else {
+            merged.push(character.clone());
+            continue;
+        };
+        for cat in &character.streaks {
+            let Some(own) = mine.streaks.iter_mut().find(|s| s.name == cat.name) else {
+                mine.streaks.push(cat.clone());
+                continue;
+            };
+            if cat.best > own.best {
+                own.best = cat.best;
+                own.best_date = cat.best_date.clone();
+            }
+            if current == Current::Theirs {
+                own.current = cat.current;
+                own.loss_run = cat.loss_run;
+            }
+        }
+    }
+    merged
+}
+
+/// What merging turned `before` into `after`, one line per change, e.g.
+/// `The Nurse: 4k best 12 -> 15`.
+pub fn describe(before: &[Character], after: &[Character]) -> Vec<String> {
+    let mut lines = Vec::new();
+    for character in after {
+        let Some(old) = before.iter().find(|c| c.name == character.name) else {
+            lines.push(format!(
+                "New character {} ({} categories)",
+                character.name,
+                character.streaks.len()
+            ));
+            continue;
+        };
+        for cat in &character.streaks {
+            let Some(old_cat) = old.streaks.iter().find(|s| s.name == cat.name) else {
+                lines.push(format!(
+                    "{}: new category {} (best {})",
+                    character.name, cat.name, cat.best
+                ));
+                continue;
+            };
+            if cat.best != old_cat.best {
+                lines.push(format!(
+                    "{}: {} best {} -> {}",
+                    character.name, cat.name, old_cat.best, cat.best
+                ));
+            }
+            if cat.current != old_cat.current {
+                lines.push(format!(
+                    "{}: {} current {} -> {}",
+                    character.name, cat.name, old_cat.current, cat.current
+                ));
+            }
+        }
+    }
+    lines
+}
+
+/// Write `characters` to `path` as a current-version save file.
+pub fn write(path: &Path, characters: &[Character]) -> Result<(), Box<dyn Error>> {
+    let text = serde_json::to_string_pretty(&schema::SaveFileRef::new(characters))?;
+    fs::write(path, text).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(())
+}
@@ -0,0 +1,42 @@
+//! Numbers as the UI shows them, with thousands grouped the way the system's locale
+//! writes them (`1,234`, `1.234`, `1 234`). Saved values stay plain integers.
+use std::sync::OnceLock;
+
+/// `n` with its thousands grouped for the system locale.
+pub fn format(n: i32) -> String {
+    static SEPARATOR: OnceLock<char> = OnceLock::new();
+    let separator = *SEPARATOR
+        .get_or_init(|| sys_locale::get_locale().map_or(',', |locale| separator_for(&locale)));
+    group(n, separator)
+}
+
+/// The thousands separator for a locale tag like `en-US` or `de_DE.UTF-8`.
+fn separator_for(locale: &str) -> char {
+    let mut parts = locale.split(['-', '_', '.']);
+    let language = parts.next().unwrap_or_default().to_lowercase();
+    let region = parts.next().unwrap_or_default().to_uppercase();
+    if region == "CH" && matches!(language.as_str(), "de" | "it" | "fr") {
+        return '\'';
+    }
+    match language.as_str() {
+        "de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" | "el" | "ro" | "hr" | "sl" => '.',
+        // A no-break space, so a number never wraps in the middle.
+        "fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "uk" | "hu" | "bg" => '\u{a0}',
+        _ => ',',
+    }
+}
+
+fn group(n: i32, separator: char) -> String {
+    let digits = n.unsigned_abs().to_string();
+    let mut text = String::new();
+    if n < 0 {
+        text.push('-');
+    }
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            text.push(separator);
+        }
+        text.push(digit);
+    }
+    text
+}
@@ -0,0 +1,71 @@
+//! Plain-text files describing the selected streak, for use as OBS Text sources.
+use crate::Character;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fs, path::Path};
+
+/// Folder for the optional one-file-per-category output.
+const CATEGORY_DIR: &str = "overlay";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct OverlayConfig {
+    pub enabled: bool,
+    pub file: String,
+    /// Placeholders: `{name}`, `{category}`, `{current}` and `{best}`.
+    pub template: String,
+    /// Also write `overlay/<category>.txt` for every category of the selected character.
+    pub per_category: bool,
+}
+
+impl Default for OverlayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            file: "overlay.txt".into(),
+            template: "{name}: {current} (PB {best})".into(),
+            per_category: false,
+        }
+    }
+}
+
+/// Fill in the template for one category of a character.
+pub fn render(template: &str, character: &Character, streak_idx: usize) -> String {
+    let Some(cat) = character.streaks.get(streak_idx) else {
+        return String::new();
+    };
+    template
+        .replace("{name}", &character.name)
+        .replace("{category}", &cat.name)
+        .replace("{current}", &cat.current.to_string())
+        .replace("{best}", &cat.best.to_string())
+}
+
+/// Write the overlay file(s) for the selected category. Relative paths in `config`
+/// are resolved against `base`.
+pub fn write(
+    config: &OverlayConfig,
+    base: &Path,
+    character: &Character,
+    streak_idx: usize,
+) -> Result<(), Box<dyn Error>> {
+    fs::write(
+        base.join(&config.file),
+        render(&config.template, character, streak_idx),
+    )?;
+    if config.per_category {
+        let category_dir = base.join(CATEGORY_DIR);
+        fs::create_dir_all(&category_dir)?;
+        for (i, cat) in character.streaks.iter().enumerate() {
+            let file_name: String = cat
+                .name
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                .collect();
+            fs::write(
+                category_dir.join(format!("{}.txt", file_name)),
+                render(&config.template, character, i),
+            )?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,172 @@
+//! Where the app keeps its files. Saves, profiles and `media/` live under one base
+//! directory, and `config.toml` next to them or in the OS config directory, so
+//! launching from a shortcut finds the same data.
+//!
+//! A portable copy (with `portable.txt` next to the executable) keeps everything next
+//! to the executable. An installed one uses the OS data directory, with the config in
+//! the OS config directory; only on Linux (`$XDG_DATA_HOME` and `$XDG_CONFIG_HOME`)
+//! are those two different folders.
+use crate::{
+    aliases::ALIASES_FILE, config::CONFIG_FILE, history::HISTORY_FILE, profile::PROFILES_DIR,
+    promotions::PROMOTIONS_FILE, session::SESSION_FILE, BACKUP_DIR, KILLER_STREAKS_FILE, MEDIA_DIR,
+    STREAKS_FILE, SURVIVOR_STREAKS_FILE,
+};
+use std::{
+    env,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Environment variable that overrides the base directory, like `--data-dir`.
+pub const DATA_DIR_ENV: &str = "DBD_WINSTREAK_DIR";
+/// Sentinel next to the executable that turns on portable mode.
+pub const PORTABLE_FILE: &str = "portable.txt";
+/// Folder created under the OS data and config directories when nothing else applies.
+const APP_DIR_NAME: &str = "dbd-winstreak";
+/// What an older copy kept next to its saves, copied over when moving to the OS folders.
+const DATA_ENTRIES: &[&str] = &[
+    STREAKS_FILE,
+    KILLER_STREAKS_FILE,
+    SURVIVOR_STREAKS_FILE,
+    PROMOTIONS_FILE,
+    HISTORY_FILE,
+    ALIASES_FILE,
+    SESSION_FILE,
+    PROFILES_DIR,
+    BACKUP_DIR,
+    MEDIA_DIR,
+    "sounds",
+];
+
+/// The folder holding `config.toml` and the base folder for everything else.
+#[derive(Debug, Clone)]
+pub struct Dirs {
+    pub config: PathBuf,
+    pub data: PathBuf,
+}
+
+impl Dirs {
+    fn same(dir: PathBuf) -> Self {
+        Self {
+            config: dir.clone(),
+            data: dir,
+        }
+    }
+}
+
+fn exe_dir() -> Option<PathBuf> {
+    env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+}
+
+/// The folders to use: `override_dir` for both if given (from `--data-dir` or the
+/// environment), else the executable's directory in portable mode, else the OS data
+/// and config directories.
+pub fn resolve(override_dir: Option<PathBuf>) -> Dirs {
+    if let Some(dir) = override_dir {
+        return Dirs::same(dir);
+    }
+    if let Some(dir) = exe_dir().filter(|d| d.join(PORTABLE_FILE).is_file()) {
+        return Dirs::same(dir);
+    }
+    installed_dirs().unwrap_or_else(|| Dirs::same(PathBuf::from(".")))
+}
+
+fn installed_dirs() -> Option<Dirs> {
+    Some(Dirs {
+        config: dirs::config_dir()?.join(APP_DIR_NAME),
+        data: dirs::data_dir()?.join(APP_DIR_NAME),
+    })
+}
+
+/// On the first launch of an installed copy, copy the saves of an older one into
+/// `dirs`: from next to the executable or the working directory, where copies before
+/// portable mode kept them, or from the config directory on Linux, where they were
+/// kept before data and config were split. The originals are left in place.
+pub fn migrate_legacy(dirs: &Dirs) {
+    if dirs.data.join(STREAKS_FILE).exists() {
+        return;
+    }
+    let Some(installed) = installed_dirs() else {
+        return;
+    };
+    if installed.data != dirs.data {
+        return;
+    }
+    let Some(old) = Some(installed.config)
+        .filter(|config| *config != installed.data)
+        .into_iter()
+        .chain(exe_dir())
+        .chain(env::current_dir().ok())
+        .find(|dir| dir.join(STREAKS_FILE).is_file())
+    else {
+        return;
+    };
+    match copy_saves(&old, dirs) {
+        Ok(()) => log::info!(
+            "Copied the saves from {} to {}; the old copies can be deleted",
+            old.display(),
+            dirs.data.display()
+        ),
+        Err(e) => log::warn!("Could not copy the saves from {}: {}", old.display(), e),
+    }
+}
+
+fn copy_saves(from: &Path, to: &Dirs) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(&to.data)?;
+    for name in DATA_ENTRIES {
+        let source = from.join(name);
+        let dest = to.data.join(name);
+        if source.is_dir() {
+            copy_dir(&source, &dest)?;
+        } else if source.is_file() {
+            fs::copy(&source, &dest)?;
+        }
+    }
+    let config = from.join(CONFIG_FILE);
+    if config.is_file() && !to.config.join(CONFIG_FILE).exists() {
+        fs::create_dir_all(&to.config)?;
+        fs::copy(&config, to.config.join(CONFIG_FILE))?;
+    }
+    Ok(())
+}
+
+/// Give a new base directory the portraits shipped with the app, by copying the
+/// `media` folder next to the executable (or in the working directory) if the base
+/// has none yet.
+pub fn seed_media(base: &Path) {
+    let target = base.join(MEDIA_DIR);
+    if target.exists() {
+        return;
+    }
+    let Some(source) = exe_dir()
+        .into_iter()
+        .chain(env::current_dir().ok())
+        .map(|dir| dir.join(MEDIA_DIR))
+        .find(|dir| dir.is_dir())
+    else {
+        return;
+    };
+    match copy_dir(&source, &target) {
+        Ok(()) => log::info!("Copied portraits from {}", source.display()),
+        Err(e) => log::warn!("Could not copy {}: {}", source.display(), e),
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let dest = to.join(entry.file_name());
+        let kind = entry.file_type()?;
+        // Symlinks are skipped, like symlinked folders in the media scan.
+        if kind.is_dir() {
+            copy_dir(&entry.path(), &dest)?;
+        } else if kind.is_file() {
+            fs::copy(entry.path(), dest)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,108 @@
+//! Character portraits. The most recently shown ones stay decoded, so switching back
+//! to a killer doesn't read and decode its image again.
+use crate::PLACEHOLDER_PORTRAIT;
+use serde::{Deserialize, Serialize};
+use slint::Image;
+use std::{
+    cell::RefCell,
+    error::Error,
+    fs,
+    io::Cursor,
+    path::{Path, PathBuf},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PortraitConfig {
+    /// How many decoded portraits to keep; 0 loads every one from disk.
+    pub cache_size: usize,
+    /// Shrink portraits added while the app runs so neither side exceeds `max_size`.
+    pub resize: bool,
+    pub max_size: u32,
+}
+
+impl Default for PortraitConfig {
+    fn default() -> Self {
+        Self {
+            cache_size: 32,
+            resize: false,
+            max_size: 256,
+        }
+    }
+}
+
+/// Scale the image at `path` down to fit in `max_size` x `max_size`, keeping its
+/// aspect ratio, and write it back in the same format. Smaller images are left alone.
+/// Returns whether the file was changed; on errors it is untouched.
+pub fn shrink(path: &Path, max_size: u32) -> Result<bool, Box<dyn Error>> {
+    let image = image::open(path)?;
+    if image.width() <= max_size && image.height() <= max_size {
+        return Ok(false);
+    }
+    let format = image::ImageFormat::from_path(path)?;
+    let mut resized = image.resize(max_size, max_size, image::imageops::FilterType::Lanczos3);
+    // JPEG has no alpha channel.
+    if format == image::ImageFormat::Jpeg {
+        resized = resized.to_rgb8().into();
+    }
+    // Encoded in memory first, so a failure can't leave half a file behind.
+    let mut bytes = Vec::new();
+    resized.write_to(&mut Cursor::new(&mut bytes), format)?;
+    fs::write(path, bytes)?;
+    Ok(true)
+}
+
+pub struct Portraits {
+    base: PathBuf,
+    capacity: usize,
+    /// Least recently used first.
+    cache: RefCell<Vec<(String, Image)>>,
+}
+
+impl Portraits {
+    /// Portraits with paths relative to `base`.
+    pub fn new(base: PathBuf, config: &PortraitConfig) -> Self {
+        Self {
+            base,
+            capacity: config.cache_size,
+            cache: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// The portrait at `path`, from the cache if it was shown recently.
+    pub fn get(&self, path: &str) -> Image {
+        let mut cache = self.cache.borrow_mut();
+        if let Some(pos) = cache.iter().position(|(p, _)| p == path) {
+            let entry = cache.remove(pos);
+            let image = entry.1.clone();
+            cache.push(entry);
+            return image;
+        }
+        let image = self.load(path);
+        if self.capacity > 0 {
+            if cache.len() >= self.capacity {
+                cache.remove(0);
+            }
+            cache.push((path.to_string(), image.clone()));
+        }
+        image
+    }
+
+    /// Drop every cached portrait, e.g. after files in `media` changed.
+    pub fn clear(&self) {
+        self.cache.borrow_mut().clear();
+    }
+
+    /// Load a portrait, falling back to the bundled placeholder.
+    fn load(&self, path: &str) -> Image {
+        let placeholder = || Image::load_from_svg_data(PLACEHOLDER_PORTRAIT).unwrap_or_default();
+        // Characters seeded without a portrait have no path at all.
+        if path.is_empty() {
+            return placeholder();
+        }
+        Image::load_from_path(&self.base.join(path)).unwrap_or_else(|e| {
+            log::warn!("Could not load portrait {}: {}", path, e);
+            placeholder()
+        })
+    }
+}
@@ -0,0 +1,42 @@
+//! Named save slots. Each profile keeps its own streaks and category files under
+//! `profiles/<name>/`; the default profile uses the top-level files.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub const PROFILES_DIR: &str = "profiles";
+pub const DEFAULT_PROFILE: &str = "Default";
+
+/// Directory under `base` holding a profile's data files.
+pub fn profile_dir(base: &Path, name: &str) -> PathBuf {
+    if name.is_empty() || name == DEFAULT_PROFILE {
+        base.to_path_buf()
+    } else {
+        base.join(PROFILES_DIR).join(name)
+    }
+}
+
+/// Whether `name` can be used as a folder name for a new profile.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != DEFAULT_PROFILE
+        && !name.starts_with('.')
+        && !name.contains(['/', '\\', ':', '*', '?', '"', '<', '>', '|'])
+}
+
+/// Every profile under `base`, with the default profile first.
+pub fn list_profiles(base: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(base.join(PROFILES_DIR))
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter(|e| e.path().is_dir())
+                .filter_map(|e| e.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    names
+}
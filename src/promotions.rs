@@ -0,0 +1,86 @@
+//! Promotion rules: a win that counts towards a harder category also counts towards an
+//! easier one, e.g. a 4k is also a 3k. Rules live in `promotions.txt` as `from -> to`.
+use std::{
+    error::Error,
+    fs,
+    io::{ErrorKind, Write},
+    path::Path,
+};
+
+pub const PROMOTIONS_FILE: &str = "promotions.txt";
+/// Rules written to a new `promotions.txt`, matching the app's original behaviour.
+const DEFAULT_RULES: &[(&str, &str)] = &[("4k", "3k")];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    /// Category whose best is carried over.
+    pub from: String,
+    /// Category whose best is raised to match.
+    pub to: String,
+}
+
+/// Parse `from -> to` lines, skipping blanks, `#` comments and malformed lines.
+fn parse(text: &str) -> Vec<Rule> {
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .filter_map(|line| {
+            let rule = line.split_once("->").and_then(|(from, to)| {
+                let (from, to) = (from.trim(), to.trim());
+                (!from.is_empty() && !to.is_empty() && from != to).then(|| Rule {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                })
+            });
+            if rule.is_none() {
+                log::warn!("Ignoring promotion rule {:?} in {}", line, PROMOTIONS_FILE);
+            }
+            rule
+        })
+        .collect()
+}
+
+/// Load the rules in `dir`, creating the file with the default rules if it's missing.
+pub fn load(dir: &Path) -> Vec<Rule> {
+    let path = dir.join(PROMOTIONS_FILE);
+    match fs::read_to_string(&path) {
+        Ok(text) => parse(&text),
+        Err(e) => {
+            if e.kind() != ErrorKind::NotFound {
+                log::warn!("Could not read {}: {}", path.display(), e);
+            } else if let Err(e) = create_default_file(&path) {
+                log::warn!("Could not create {}: {}", path.display(), e);
+            }
+            DEFAULT_RULES
+                .iter()
+                .map(|&(from, to)| Rule {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                })
+                .collect()
+        }
+    }
+}
+
+fn create_default_file(path: &Path) -> Result<(), Box<dyn Error>> {
+    let mut file = fs::File::create(path)?;
+    writeln!(file, "# Promotion Rules")?;
+    writeln!(
+        file,
+        "# \"from -> to\" means a win in \"from\" also counts as one in \"to\":"
+    )?;
+    writeln!(
+        file,
+        "# whenever you win, the best of \"to\" is raised to the best of \"from\"."
+    )?;
+    writeln!(
+        file,
+        "# Lines starting with # are comments and will be ignored."
+    )?;
+    writeln!(file, "#")?;
+    writeln!(file, "# Example: Perkless 4k -> Perkless 3k")?;
+    for &(from, to) in DEFAULT_RULES {
+        writeln!(file, "{} -> {}", from, to)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,78 @@
+//! Markdown reports of a character's streaks, for pasting into Discord and the like.
+use crate::{Character, StreakCategory};
+use std::{
+    error::Error,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Write `report-<name>.md` for one character into `dir`, returning its path.
+pub fn write_character(dir: &Path, character: &Character) -> Result<PathBuf, Box<dyn Error>> {
+    let mut text = header();
+    render_character(&mut text, character);
+    let name = slug(&character.name);
+    save(
+        dir,
+        if name.is_empty() { "character" } else { &name },
+        &text,
+    )
+}
+
+/// Write `report-all.md` covering every character into `dir`, returning its path.
+pub fn write_all(dir: &Path, characters: &[Character]) -> Result<PathBuf, Box<dyn Error>> {
+    let mut text = header();
+    for character in characters {
+        render_character(&mut text, character);
+    }
+    let (wins, losses) = totals(characters.iter().flat_map(|c| &c.streaks));
+    let _ = writeln!(text, "**All characters:** {} wins, {} losses", wins, losses);
+    save(dir, "all", &text)
+}
+
+fn header() -> String {
+    format!(
+        "_DBD Winstreak report, {}_\n\n",
+        chrono::Local::now().date_naive()
+    )
+}
+
+fn render_character(text: &mut String, character: &Character) {
+    let _ = writeln!(text, "## {}\n", character.name);
+    let _ = writeln!(text, "| Category | Current | Best | Wins | Losses |");
+    let _ = writeln!(text, "|---|---:|---:|---:|---:|");
+    for cat in &character.streaks {
+        let _ = writeln!(
+            text,
+            "| {} | {} | {} | {} | {} |",
+            cat.name.replace('|', "\\|"),
+            cat.current,
+            cat.best,
+            cat.wins,
+            cat.losses
+        );
+    }
+    let (wins, losses) = totals(&character.streaks);
+    let _ = writeln!(text, "| **Total** | | | {} | {} |\n", wins, losses);
+}
+
+fn totals<'a>(streaks: impl IntoIterator<Item = &'a StreakCategory>) -> (i32, i32) {
+    streaks
+        .into_iter()
+        .fold((0, 0), |(w, l), cat| (w + cat.wins, l + cat.losses))
+}
+
+/// `The Nurse` becomes `the-nurse`, so the name is safe in a file name.
+fn slug(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn save(dir: &Path, name: &str, text: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let path = dir.join(format!("report-{}.md", name));
+    fs::write(&path, text)?;
+    Ok(path)
+}
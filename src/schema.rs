@@ -0,0 +1,99 @@
+//! On-disk layout of `streaks.json`. The file is a versioned wrapper around the
+//! character list; older layouts are migrated one version at a time when loaded.
+use crate::{Character, Role};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{error::Error, fmt};
+
+/// Version written by `save_data`.
+pub const CURRENT_VERSION: u32 = 2;
+
+/// A save written by a newer build of the app. Unlike a corrupt file it's fine as it
+/// is, so it must be left alone rather than replaced.
+#[derive(Debug)]
+pub struct NewerVersion {
+    pub version: u32,
+}
+
+impl fmt::Display for NewerVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "save file version {} is newer than this app supports ({})",
+            self.version, CURRENT_VERSION
+        )
+    }
+}
+
+impl Error for NewerVersion {}
+
+#[derive(Deserialize)]
+struct SaveFile {
+    version: u32,
+    characters: Vec<Character>,
+}
+
+#[derive(Serialize)]
+pub struct SaveFileRef<'a> {
+    version: u32,
+    characters: &'a [Character],
+}
+
+impl<'a> SaveFileRef<'a> {
+    pub fn new(characters: &'a [Character]) -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            characters,
+        }
+    }
+}
+
+/// Migrations indexed by the version they upgrade from.
+const MIGRATIONS: &[fn(Value) -> Value] = &[migrate_v0, migrate_v1];
+
+/// Version 0 was a bare array of characters.
+fn migrate_v0(value: Value) -> Value {
+    json!({ "version": 1, "characters": value })
+}
+
+/// Version 1 had no roles; a character named "Survivor" was the only survivor.
+fn migrate_v1(mut value: Value) -> Value {
+    if let Some(characters) = value.get_mut("characters").and_then(Value::as_array_mut) {
+        for character in characters.iter_mut().filter_map(Value::as_object_mut) {
+            let name = character.get("name").and_then(Value::as_str).unwrap_or("");
+            let role = Role::from_name(name);
+            character.insert(
+                "role".into(),
+                serde_json::to_value(role).unwrap_or_default(),
+            );
+        }
+    }
+    value["version"] = json!(2);
+    value
+}
+
+/// Parse a save file of any known version. Returns the characters and whether the
+/// file was migrated and should be rewritten.
+pub fn parse(text: &str) -> Result<(Vec<Character>, bool), Box<dyn Error>> {
+    let mut value: Value = serde_json::from_str(text)?;
+    let mut version = if value.is_array() {
+        0
+    } else {
+        value
+            .get("version")
+            .and_then(Value::as_u64)
+            .ok_or("save file has no version")? as u32
+    };
+    if version > CURRENT_VERSION {
+        return Err(NewerVersion { version }.into());
+    }
+
+    let migrated = version < CURRENT_VERSION;
+    while version < CURRENT_VERSION {
+        value = MIGRATIONS[version as usize](value);
+        version += 1;
+    }
+    let file: SaveFile = serde_json::from_value(value)?;
+    debug_assert_eq!(file.version, CURRENT_VERSION);
+    Ok((file.characters, migrated))
+}
@@ -0,0 +1,48 @@
+//! The streak length after every recorded match, as CSV for plotting in a spreadsheet.
+use crate::history::HistoryEntry;
+use std::{
+    error::Error,
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+pub const SERIES_FILE: &str = "streak-series.csv";
+
+/// Write `streak-series.csv` into `dir` from `entries`, keeping only one character's
+/// category if `only` is `(character, category)`. Returns the file's path.
+pub fn write(
+    dir: &Path,
+    entries: &[HistoryEntry],
+    only: Option<(&str, &str)>,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let path = dir.join(SERIES_FILE);
+    let mut out = BufWriter::new(File::create(&path)?);
+    writeln!(out, "timestamp,character,category,current_after")?;
+    for entry in entries {
+        if only.is_some_and(|(character, category)| {
+            entry.character != character || entry.category != category
+        }) {
+            continue;
+        }
+        writeln!(
+            out,
+            "{},{},{},{}",
+            field(&entry.timestamp),
+            field(&entry.character),
+            field(&entry.category),
+            entry.current
+        )?;
+    }
+    out.flush()?;
+    Ok(path)
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break.
+fn field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,56 @@
+//! The last selected character and category, restored on the next launch. Stored by
+//! name so re-sorting or adding characters doesn't point it somewhere else.
+use crate::Character;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fs, path::Path};
+
+pub const SESSION_FILE: &str = "session.json";
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Session {
+    pub character: String,
+    pub category: String,
+}
+
+impl Session {
+    pub fn capture(characters: &[Character], char_idx: usize, streak_idx: usize) -> Self {
+        let character = characters.get(char_idx);
+        Self {
+            character: character.map(|c| c.name.clone()).unwrap_or_default(),
+            category: character
+                .and_then(|c| c.streaks.get(streak_idx))
+                .map(|s| s.name.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// The `(char_idx, streak_idx)` this session points at, falling back to the first
+    /// character or category when a saved name no longer exists.
+    pub fn resolve(&self, characters: &[Character]) -> (usize, usize) {
+        let Some(char_idx) = characters.iter().position(|c| c.name == self.character) else {
+            return (0, 0);
+        };
+        let streak_idx = characters[char_idx]
+            .streaks
+            .iter()
+            .position(|s| s.name == self.category)
+            .unwrap_or(0);
+        (char_idx, streak_idx)
+    }
+}
+
+/// Load the session from `dir`, or an empty one if it's missing or unreadable.
+pub fn load(dir: &Path) -> Session {
+    fs::read_to_string(dir.join(SESSION_FILE))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(dir: &Path, session: &Session) -> Result<(), Box<dyn Error>> {
+    fs::write(
+        dir.join(SESSION_FILE),
+        serde_json::to_string_pretty(session)?,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,115 @@
+//! Short sound effects for streak events, played with `rodio`. On Linux, where `rodio`
+//! would need the ALSA libraries at build time, `paplay` or `aplay` plays them instead.
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SoundConfig {
+    pub enabled: bool,
+    /// Played when a win sets a new personal best.
+    pub pb: String,
+    /// Played on a loss.
+    pub loss: String,
+}
+
+impl Default for SoundConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            pb: "sounds/pb.wav".into(),
+            loss: "sounds/loss.wav".into(),
+        }
+    }
+}
+
+pub struct Sounds {
+    // Playback stops when the output stream is dropped, so it lives as long as we do.
+    #[cfg(not(target_os = "linux"))]
+    sink: Option<rodio::MixerDeviceSink>,
+    /// Set once neither player could be started, so that's only reported once.
+    #[cfg(target_os = "linux")]
+    no_player: std::cell::Cell<bool>,
+}
+
+impl Sounds {
+    pub fn new() -> Self {
+        #[cfg(not(target_os = "linux"))]
+        {
+            let sink = rodio::DeviceSinkBuilder::open_default_sink()
+                .map_err(|e| log::warn!("No audio output available: {}", e))
+                .ok()
+                .map(|mut sink| {
+                    sink.log_on_drop(false);
+                    sink
+                });
+            Self { sink }
+        }
+        #[cfg(target_os = "linux")]
+        Self {
+            no_player: std::cell::Cell::new(false),
+        }
+    }
+
+    /// Play a sound file in the background. Missing files are skipped silently so
+    /// sounds can be opted into just by adding them.
+    pub fn play(&self, path: &Path) {
+        if !path.is_file() {
+            return;
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let Some(sink) = &self.sink else {
+                return;
+            };
+            let result = std::fs::File::open(path)
+                .map_err(|e| e.to_string())
+                .and_then(|file| {
+                    rodio::play(sink.mixer(), std::io::BufReader::new(file))
+                        .map_err(|e| e.to_string())
+                });
+            match result {
+                Ok(player) => player.detach(),
+                Err(e) => log::warn!("Could not play {}: {}", path.display(), e),
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            use std::process::{Command, Stdio};
+            if self.no_player.get() {
+                return;
+            }
+            let spawn = |program: &str, args: &[&str]| {
+                Command::new(program)
+                    .args(args)
+                    .arg(path)
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::piped())
+                    .spawn()
+            };
+            let child = spawn("paplay", &[]).or_else(|_| spawn("aplay", &["-q"]));
+            match child {
+                // Wait in the background, which also reaps the player, and report a
+                // file it couldn't play.
+                Ok(child) => {
+                    let path = path.to_path_buf();
+                    std::thread::spawn(move || match child.wait_with_output() {
+                        Ok(output) if !output.status.success() => log::warn!(
+                            "Could not play {}: {}",
+                            path.display(),
+                            String::from_utf8_lossy(&output.stderr).trim()
+                        ),
+                        Ok(_) => {}
+                        Err(e) => log::warn!("Could not play {}: {}", path.display(), e),
+                    });
+                }
+                Err(e) => {
+                    log::warn!("Could not play sounds; install paplay or aplay ({})", e);
+                    self.no_player.set(true);
+                }
+            }
+        }
+    }
+}
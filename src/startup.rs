@@ -0,0 +1,58 @@
+//! Starting the app when the user logs in to Windows, through a value under the
+//! current user's `Run` registry key. Other platforms have no such option, and turning
+//! it on there does nothing.
+
+/// Whether this build can start with the OS at all.
+pub const SUPPORTED: bool = cfg!(windows);
+
+#[cfg(windows)]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+#[cfg(windows)]
+const VALUE_NAME: &str = "DBD Winstreak";
+
+/// Add the entry that starts the running executable at login, or remove it. The
+/// error is a message to show the user, e.g. when the registry write is denied.
+#[cfg(windows)]
+pub fn set_enabled(enabled: bool) -> Result<(), String> {
+    if enabled {
+        let exe = std::env::current_exe()
+            .map_err(|e| format!("Could not find the app's executable: {}", e))?;
+        let command = format!("\"{}\"", exe.display());
+        reg(&[
+            "add", RUN_KEY, "/v", VALUE_NAME, "/t", "REG_SZ", "/d", &command, "/f",
+        ])
+    } else if reg(&["query", RUN_KEY, "/v", VALUE_NAME]).is_ok() {
+        reg(&["delete", RUN_KEY, "/v", VALUE_NAME, "/f"])
+    } else {
+        // Already gone; deleting would only fail.
+        Ok(())
+    }
+}
+
+#[cfg(not(windows))]
+pub fn set_enabled(_enabled: bool) -> Result<(), String> {
+    Ok(())
+}
+
+/// Run `reg.exe` without flashing a console window, turning a failure into its message.
+#[cfg(windows)]
+fn reg(args: &[&str]) -> Result<(), String> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let output = std::process::Command::new("reg")
+        .args(args)
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| format!("Could not run reg.exe: {}", e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let reason = stderr.trim().trim_start_matches("ERROR:").trim();
+    Err(if reason.is_empty() {
+        "Windows refused to change the startup entry".into()
+    } else {
+        format!("Windows refused to change the startup entry: {}", reason)
+    })
+}
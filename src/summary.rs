@@ -0,0 +1,81 @@
+//! Wins and losses from the history log, bucketed by ISO week or by month.
+use crate::{history::HistoryEntry, SummaryRow};
+use chrono::{DateTime, Datelike, NaiveDate};
+
+/// Label shown for the row that adds up every category of a period.
+const ALL_CATEGORIES: &str = "All categories";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Period {
+    Week,
+    Month,
+}
+
+impl Period {
+    /// `2026-W07` for weeks, `2026-02` for months.
+    fn label(self, date: NaiveDate) -> String {
+        match self {
+            Period::Week => {
+                let week = date.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+            Period::Month => format!("{}-{:02}", date.year(), date.month()),
+        }
+    }
+}
+
+/// One row per category per period, newest period first, each period led by its
+/// total. With no history at all, the current period is shown with zeros.
+pub fn summarize(entries: &[HistoryEntry], period: Period) -> Vec<SummaryRow> {
+    // (period, category, wins, losses), in the order first seen.
+    let mut buckets: Vec<(String, String, i32, i32)> = Vec::new();
+    for entry in entries {
+        let Ok(time) = DateTime::parse_from_rfc3339(&entry.timestamp) else {
+            log::warn!("Skipping history entry with bad time {:?}", entry.timestamp);
+            continue;
+        };
+        let label = period.label(time.date_naive());
+        for category in [ALL_CATEGORIES, entry.category.as_str()] {
+            let bucket = match buckets
+                .iter_mut()
+                .find(|(p, c, ..)| *p == label && c == category)
+            {
+                Some(bucket) => bucket,
+                None => {
+                    buckets.push((label.clone(), category.to_string(), 0, 0));
+                    buckets.last_mut().expect("just pushed")
+                }
+            };
+            if entry.win {
+                bucket.2 += 1;
+            } else {
+                bucket.3 += 1;
+            }
+        }
+    }
+    if buckets.is_empty() {
+        let today = chrono::Local::now().date_naive();
+        buckets.push((period.label(today), ALL_CATEGORIES.to_string(), 0, 0));
+    }
+
+    // Labels sort chronologically; the total stays first within its period.
+    buckets.sort_by(|a, b| {
+        b.0.cmp(&a.0)
+            .then_with(|| (a.1 != ALL_CATEGORIES).cmp(&(b.1 != ALL_CATEGORIES)))
+            .then_with(|| a.1.cmp(&b.1))
+    });
+    buckets
+        .into_iter()
+        .map(|(period, category, wins, losses)| SummaryRow {
+            period: period.into(),
+            category: category.into(),
+            wins,
+            losses,
+            win_rate: if wins + losses == 0 {
+                0.0
+            } else {
+                wins as f32 * 100.0 / (wins + losses) as f32
+            },
+        })
+        .collect()
+}
@@ -0,0 +1,162 @@
+//! Tray icon with quick win/loss actions, so the window can be hidden while playing.
+//! Not supported on Linux: `tray-icon` needs GTK there, which this app doesn't link, so
+//! the window minimises and closes as usual.
+#[cfg(not(target_os = "linux"))]
+use std::cell::RefCell;
+#[cfg(not(target_os = "linux"))]
+use tray_icon::{
+    menu::{Menu, MenuEvent, MenuId, MenuItem, PredefinedMenuItem},
+    Icon, MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent,
+};
+
+/// Size of the generated tray icon, in pixels.
+#[cfg(not(target_os = "linux"))]
+const ICON_SIZE: u32 = 32;
+
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+pub enum TrayAction {
+    Win,
+    Loss,
+    Show,
+    Quit,
+}
+
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+pub struct Tray {
+    // Dropping the icon removes it from the tray.
+    #[cfg(not(target_os = "linux"))]
+    icon: TrayIcon,
+    #[cfg(not(target_os = "linux"))]
+    status: MenuItem,
+    #[cfg(not(target_os = "linux"))]
+    status_text: RefCell<String>,
+    #[cfg(not(target_os = "linux"))]
+    win: MenuId,
+    #[cfg(not(target_os = "linux"))]
+    loss: MenuId,
+    #[cfg(not(target_os = "linux"))]
+    show: MenuId,
+    #[cfg(not(target_os = "linux"))]
+    quit: MenuId,
+}
+
+impl Tray {
+    /// Add the tray icon, or `None` if the platform has no tray.
+    pub fn new() -> Option<Self> {
+        #[cfg(not(target_os = "linux"))]
+        {
+            let status = MenuItem::new("", false, None);
+            let win = MenuItem::new("Record Win", true, None);
+            let loss = MenuItem::new("Record Loss", true, None);
+            let show = MenuItem::new("Show window", true, None);
+            let quit = MenuItem::new("Quit", true, None);
+            let menu = Menu::new();
+            menu.append_items(&[
+                &status,
+                &PredefinedMenuItem::separator(),
+                &win,
+                &loss,
+                &PredefinedMenuItem::separator(),
+                &show,
+                &quit,
+            ])
+            .map_err(|e| log::warn!("Could not build the tray menu: {}", e))
+            .ok()?;
+            let icon = TrayIconBuilder::new()
+                .with_menu(Box::new(menu))
+                .with_menu_on_left_click(false)
+                .with_icon(icon())
+                .with_tooltip("DBD Winstreak")
+                .build()
+                .map_err(|e| log::warn!("Tray icon unavailable: {}", e))
+                .ok()?;
+            Some(Self {
+                icon,
+                status,
+                status_text: RefCell::new(String::new()),
+                win: win.id().clone(),
+                loss: loss.id().clone(),
+                show: show.id().clone(),
+                quit: quit.id().clone(),
+            })
+        }
+        #[cfg(target_os = "linux")]
+        {
+            log::info!("No tray icon on Linux; closing the window quits");
+            None
+        }
+    }
+
+    /// Drain pending menu picks and icon clicks. Clicking the icon shows the window.
+    pub fn poll(&self) -> Vec<TrayAction> {
+        #[cfg(not(target_os = "linux"))]
+        {
+            let clicks = TrayIconEvent::receiver()
+                .try_iter()
+                .filter(|e| {
+                    matches!(
+                        e,
+                        TrayIconEvent::Click {
+                            button: MouseButton::Left,
+                            button_state: MouseButtonState::Up,
+                            ..
+                        }
+                    )
+                })
+                .map(|_| TrayAction::Show);
+            let picks = MenuEvent::receiver()
+                .try_iter()
+                .filter_map(|e| {
+                    if e.id == self.win {
+                        Some(TrayAction::Win)
+                    } else if e.id == self.loss {
+                        Some(TrayAction::Loss)
+                    } else if e.id == self.show {
+                        Some(TrayAction::Show)
+                    } else if e.id == self.quit {
+                        Some(TrayAction::Quit)
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+            clicks.chain(picks).collect()
+        }
+        #[cfg(target_os = "linux")]
+        Vec::new()
+    }
+
+    /// Show `text` (the selected streak) at the top of the menu and in the tooltip.
+    pub fn set_status(&self, text: &str) {
+        #[cfg(not(target_os = "linux"))]
+        {
+            let mut status_text = self.status_text.borrow_mut();
+            if *status_text == text {
+                return;
+            }
+            self.status.set_text(text);
+            if let Err(e) = self.icon.set_tooltip(Some(text)) {
+                log::warn!("Could not update the tray tooltip: {}", e);
+            }
+            *status_text = text.to_string();
+        }
+        #[cfg(target_os = "linux")]
+        let _ = text;
+    }
+}
+
+/// A red disc, drawn here so no image file has to be shipped or decoded.
+#[cfg(not(target_os = "linux"))]
+fn icon() -> Icon {
+    let size = ICON_SIZE as f32;
+    let radius = size / 2.0 - 1.0;
+    let rgba = (0..ICON_SIZE * ICON_SIZE)
+        .flat_map(|i| {
+            let x = (i % ICON_SIZE) as f32 + 0.5 - size / 2.0;
+            let y = (i / ICON_SIZE) as f32 + 0.5 - size / 2.0;
+            let alpha = (radius - (x * x + y * y).sqrt() + 0.5).clamp(0.0, 1.0);
+            [0xB0, 0x10, 0x10, (alpha * 255.0) as u8]
+        })
+        .collect();
+    Icon::from_rgba(rgba, ICON_SIZE, ICON_SIZE).expect("icon buffer matches its size")
+}
@@ -0,0 +1,162 @@
+//! Twitch chat bot: answers a command such as `!streak` in the streamer's channel with
+//! the selected streak, read from the UI's shared state like the HTTP server does.
+use crate::{lock, overlay, Character};
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    io::{BufRead, BufReader, Write},
+    net::TcpStream,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Chat over TLS, since logging in sends the OAuth token.
+const HOST: &str = "irc.chat.twitch.tv";
+const PORT: u16 = 6697;
+/// Wait after losing the connection before trying again.
+const RECONNECT_AFTER: Duration = Duration::from_secs(30);
+/// Minimum time between replies, so a busy chat can't get the bot rate limited.
+const REPLY_COOLDOWN: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct TwitchConfig {
+    pub enabled: bool,
+    /// Channel to join, without the `#`.
+    pub channel: String,
+    /// Account the bot logs in as, and its OAuth token (with or without `oauth:`).
+    pub username: String,
+    pub token: String,
+    pub command: String,
+    /// Placeholders as in the overlay template.
+    pub template: String,
+}
+
+impl Default for TwitchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            channel: String::new(),
+            username: String::new(),
+            token: String::new(),
+            command: "!streak".into(),
+            template: "{name} {category} streak: {current} (PB {best})".into(),
+        }
+    }
+}
+
+/// Connect in a background thread, reconnecting whenever the connection drops. A
+/// config without a channel or login is a warning.
+pub fn start(
+    config: &TwitchConfig,
+    characters: Arc<Mutex<Vec<Character>>>,
+    char_idx: Arc<AtomicUsize>,
+    streak_idx: Arc<AtomicUsize>,
+) {
+    if config.channel.is_empty() || config.username.is_empty() || config.token.is_empty() {
+        log::warn!("Twitch chat needs a channel, username and token in the config");
+        return;
+    }
+    let config = config.clone();
+    thread::spawn(move || loop {
+        if let Err(e) = run(&config, &characters, &char_idx, &streak_idx) {
+            log::warn!("Twitch chat disconnected: {}", e);
+        }
+        thread::sleep(RECONNECT_AFTER);
+    });
+}
+
+/// One connection, from logging in until it fails.
+fn run(
+    config: &TwitchConfig,
+    characters: &Mutex<Vec<Character>>,
+    char_idx: &AtomicUsize,
+    streak_idx: &AtomicUsize,
+) -> Result<(), Box<dyn Error>> {
+    let mut reader = BufReader::new(connect()?);
+    let stream = reader.get_mut();
+    let channel = format!("#{}", config.channel.trim_start_matches('#').to_lowercase());
+    let token = config.token.trim_start_matches("oauth:");
+    write!(
+        stream,
+        "PASS oauth:{}\r\nNICK {}\r\nJOIN {}\r\n",
+        token,
+        config.username.to_lowercase(),
+        channel
+    )?;
+    log::info!("Answering {} in Twitch chat {}", config.command, channel);
+
+    let mut last_reply: Option<Instant> = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        let stream = reader.get_mut();
+        if let Some(server) = line.strip_prefix("PING ") {
+            write!(stream, "PONG {}\r\n", server)?;
+            continue;
+        }
+        if line.contains(" NOTICE * :Login authentication failed") {
+            return Err("login failed; check the username and token".into());
+        }
+        let Some(message) = chat_message(line, &channel) else {
+            continue;
+        };
+        if !message.trim().eq_ignore_ascii_case(&config.command)
+            || last_reply.is_some_and(|at| at.elapsed() < REPLY_COOLDOWN)
+        {
+            continue;
+        }
+        let reply = {
+            let list = lock(characters);
+            list.get(char_idx.load(Ordering::Relaxed)).map(|character| {
+                overlay::render(
+                    &config.template,
+                    character,
+                    streak_idx.load(Ordering::Relaxed),
+                )
+                // Names are user text; a line break in one would end the message and
+                // start another IRC command.
+                .replace(['\r', '\n'], " ")
+            })
+        };
+        if let Some(reply) = reply.filter(|r| !r.is_empty()) {
+            write!(stream, "PRIVMSG {} :{}\r\n", channel, reply)?;
+            last_reply = Some(Instant::now());
+        }
+    }
+    Err("connection closed".into())
+}
+
+/// A TLS connection to Twitch chat, checked against the bundled root certificates.
+fn connect() -> Result<rustls::StreamOwned<rustls::ClientConnection, TcpStream>, Box<dyn Error>> {
+    let roots = rustls::RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    let tls = rustls::ClientConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()?
+    .with_root_certificates(roots)
+    .with_no_client_auth();
+    let connection = rustls::ClientConnection::new(Arc::new(tls), HOST.try_into()?)?;
+    Ok(rustls::StreamOwned::new(
+        connection,
+        TcpStream::connect((HOST, PORT))?,
+    ))
+}
+
+/// The text of a chat message sent to `channel`, from a line like
+/// `:nick!nick@nick.tmi.twitch.tv PRIVMSG #channel :!streak`.
+fn chat_message<'a>(line: &'a str, channel: &str) -> Option<&'a str> {
+    let (_, rest) = line.split_once(" PRIVMSG ")?;
+    let (target, message) = rest.split_once(" :")?;
+    (target == channel).then_some(message)
+}
@@ -0,0 +1,121 @@
+//! Looking up the latest release on GitHub at startup, so a newer version can be
+//! pointed out. Off until the user turns it on, since it contacts GitHub. The request
+//! runs on its own thread, and being offline or rate limited only shows up in the log.
+use serde::{Deserialize, Serialize};
+use std::{error::Error, process::Command, thread, time::Duration};
+
+const LATEST_RELEASE: &str =
+    "https://api.github.com/repos/randomdump/dbd-winstreak-ui/releases/latest";
+/// Release pages are the only links opened, whatever the API answers.
+const RELEASE_PAGES: &str = "https://github.com/";
+/// Long enough for a slow connection, short enough not to keep a thread around.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct UpdateConfig {
+    /// Look for a newer release once at startup.
+    pub check: bool,
+}
+
+/// A release newer than this build.
+pub struct Release {
+    /// The tag, e.g. `v0.3.0`.
+    pub version: String,
+    /// The release's page on GitHub.
+    pub url: String,
+}
+
+#[derive(Deserialize)]
+struct LatestRelease {
+    tag_name: String,
+    html_url: String,
+}
+
+/// Ask GitHub for the latest release in the background, and call `found` from that
+/// thread if it's newer than this build.
+pub fn check(found: impl FnOnce(Release) + Send + 'static) {
+    thread::spawn(move || match latest() {
+        Ok(release) if !release.html_url.starts_with(RELEASE_PAGES) => {
+            log::warn!(
+                "Ignoring a release with an unexpected page: {}",
+                release.html_url
+            );
+        }
+        Ok(release) if is_newer(&release.tag_name, env!("CARGO_PKG_VERSION")) => {
+            log::info!("{} is available at {}", release.tag_name, release.html_url);
+            found(Release {
+                version: release.tag_name,
+                url: release.html_url,
+            });
+        }
+        Ok(_) => log::debug!("No newer release than {}", env!("CARGO_PKG_VERSION")),
+        Err(e) => log::debug!("Could not check for updates: {}", e),
+    });
+}
+
+fn latest() -> Result<LatestRelease, Box<dyn Error>> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(TIMEOUT))
+        .user_agent(concat!("dbd-winstreak-gui/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .into();
+    let mut response = agent
+        .get(LATEST_RELEASE)
+        .header("Accept", "application/vnd.github+json")
+        .call()?;
+    let body = response.body_mut().read_to_string()?;
+    Ok(serde_json::from_str(&body)?)
+}
+
+/// Whether a release tagged `tag`, like `v0.3.0`, is a later version than `current`.
+/// Anything after a `-` or `+` is ignored, and tags that aren't a version never are.
+fn is_newer(tag: &str, current: &str) -> bool {
+    fn parts(version: &str) -> Option<Vec<u64>> {
+        let version = version.trim().trim_start_matches(['v', 'V']);
+        let numbers = version.split(['-', '+']).next()?;
+        numbers.split('.').map(|part| part.parse().ok()).collect()
+    }
+    match (parts(tag), parts(current)) {
+        (Some(mut tag), Some(mut current)) => {
+            // Missing parts count as zero, so `0.2.1.0` is the same as `0.2.1`.
+            let len = tag.len().max(current.len());
+            tag.resize(len, 0);
+            current.resize(len, 0);
+            tag > current
+        }
+        _ => false,
+    }
+}
+
+/// Open the release page `url` in the default browser. Anything that isn't a GitHub
+/// page is refused rather than handed to the system's opener.
+pub fn open_in_browser(url: &str) {
+    if !url.starts_with(RELEASE_PAGES) {
+        log::warn!("Not opening {}; it isn't a GitHub page", url);
+        return;
+    }
+    let result = if cfg!(target_os = "windows") {
+        Command::new("explorer").arg(url).spawn()
+    } else if cfg!(target_os = "macos") {
+        Command::new("open").arg(url).spawn()
+    } else {
+        Command::new("xdg-open").arg(url).spawn()
+    };
+    if let Err(e) = result {
+        log::warn!("Could not open {}: {}", url, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_newer;
+
+    #[test]
+    fn is_newer_pads_missing_parts_with_zeros() {
+        assert!(!is_newer("v0.2.1.0", "0.2.1"));
+        assert!(!is_newer("0.2.1", "0.2.1.0"));
+        assert!(is_newer("0.2.1.1", "0.2.1"));
+        assert!(is_newer("v0.3", "0.2.1"));
+    }
+}
@@ -0,0 +1,63 @@
+//! Debounced file system watching, polled from the UI thread.
+pub use notify::RecursiveMode;
+use notify::{RecommendedWatcher, Watcher};
+use std::{
+    cell::RefCell,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    time::{Duration, Instant},
+};
+
+/// How long the watched paths must stay quiet before changes are reported.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+pub struct DebouncedWatcher {
+    // Kept alive so events keep flowing into `rx`.
+    _watcher: RecommendedWatcher,
+    rx: Receiver<notify::Result<notify::Event>>,
+    pending: RefCell<Vec<PathBuf>>,
+    last_event: RefCell<Option<Instant>>,
+}
+
+impl DebouncedWatcher {
+    pub fn new(paths: &[&Path], mode: RecursiveMode) -> notify::Result<Self> {
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        for path in paths {
+            watcher.watch(path, mode)?;
+        }
+        Ok(Self {
+            _watcher: watcher,
+            rx,
+            pending: RefCell::new(Vec::new()),
+            last_event: RefCell::new(None),
+        })
+    }
+
+    /// Returns the paths that changed once a burst of events has settled.
+    pub fn poll(&self) -> Option<Vec<PathBuf>> {
+        let mut pending = self.pending.borrow_mut();
+        for event in self.rx.try_iter().filter_map(Result::ok) {
+            if event.kind.is_access() {
+                continue;
+            }
+            for path in event.paths {
+                if !pending.contains(&path) {
+                    pending.push(path);
+                }
+            }
+            *self.last_event.borrow_mut() = Some(Instant::now());
+        }
+
+        let settled = self
+            .last_event
+            .borrow()
+            .is_some_and(|t| t.elapsed() >= DEBOUNCE);
+        if settled && !pending.is_empty() {
+            *self.last_event.borrow_mut() = None;
+            Some(std::mem::take(&mut *pending))
+        } else {
+            None
+        }
+    }
+}